@@ -1,13 +1,15 @@
 #![allow(clippy::large_enum_variant)]
 
+use std::time::Duration;
 use toolkit::{
     app::App,
+    event::SystemEvent,
     glam::Vec2,
     include_asset,
     types::{Argb8888, Stroke},
     widget::{Anchor, Callbacks, Context, NoID, Sender, Spacing, StaticID, Tree, WidgetQuery},
     window::WindowRequest,
-    ContentManager, Error, EventLoop, Handle, SpecialOptions, TargetMonitor, WidgetEnum,
+    Backend, ContentManager, Error, EventLoop, Handle, SpecialOptions, TargetMonitor, WidgetEnum,
     WindowRoot,
 };
 use widgets::{
@@ -25,8 +27,14 @@ enum WindowContext {
     #[default]
     UpdateClock,
     PrintText,
+    WatchIdle,
+    /// Stops or restarts the clock while nobody looks at it.
+    SetClockRunning(bool),
 }
 
+/// Inactivity after which the clock stops updating.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
 impl Context for WindowContext {
     type Widget = Root;
     type WindowRoot = Root;
@@ -40,23 +48,38 @@ impl Context for WindowContext {
                 clock.set_text(&format!("{}", local.format("%H:%M")));
             }
             WindowContext::PrintText => println!("Hello, World!"),
+            WindowContext::WatchIdle => tree.watch_idle(IDLE_TIMEOUT),
+            WindowContext::SetClockRunning(running) => {
+                let timer = tree
+                    .get_mut_element::<Timer<Self, CallbackImpls, StaticID>>("ClockTimer")
+                    .unwrap();
+                timer.running = *running;
+            }
+        }
+    }
+
+    fn on_event(event: &SystemEvent) -> Option<Self> {
+        match event {
+            SystemEvent::Idled(IDLE_TIMEOUT) => Some(Self::SetClockRunning(false)),
+            SystemEvent::Resumed(IDLE_TIMEOUT) => Some(Self::SetClockRunning(true)),
+            _ => None,
         }
     }
 }
 
+type Start = Button<
+    WindowContext,
+    Rectangle<WindowContext, Row<WindowContext, StartButton, NoID>, NoID>,
+    CallbackImpls,
+    NoID,
+>;
+
 #[derive(WidgetEnum)]
 #[context(WindowContext)]
 //552 - Before
 //480 - After
 enum Elements {
-    Button(
-        Button<
-            WindowContext,
-            Rectangle<WindowContext, Row<WindowContext, StartButton, NoID>, NoID>,
-            CallbackImpls,
-            NoID,
-        >,
-    ),
+    Button(Start),
     Tray(Row<WindowContext, TrayElements, NoID>),
 }
 
@@ -87,7 +110,7 @@ impl Default for StartButton {
 //272 - After
 enum TrayElements {
     Text(Text<WindowContext, StaticID>),
-    Timer(Timer<WindowContext, CallbackImpls, NoID>),
+    Timer(Timer<WindowContext, CallbackImpls, StaticID>),
 }
 
 impl Default for TrayElements {
@@ -103,11 +126,12 @@ impl_proxy_widget!(Root, WindowContext);
 impl WindowRoot<WindowContext, Root> for Root {
     fn request(&self) -> WindowRequest {
         WindowRequest::new("bar")
-            .with_size(1920, 35)
+            .with_size(0, 35)
             .bottom(SpecialOptions {
-                anchor: toolkit::Anchor::Bottom,
+                anchor: toolkit::Anchor::Bottom | toolkit::Anchor::Left | toolkit::Anchor::Right,
                 exclusive_zone: 35,
                 target: TargetMonitor::Primary,
+                ..Default::default()
             })
     }
 
@@ -121,12 +145,7 @@ impl WindowRoot<WindowContext, Root> for Root {
         root.background = Argb8888::new(212, 208, 200, 255).into();
         root.stroke = Stroke::NONE;
 
-        let mut start: Button<
-            WindowContext,
-            Rectangle<WindowContext, Row<WindowContext, StartButton, NoID>, NoID>,
-            CallbackImpls,
-            NoID,
-        > = Button::new();
+        let mut start: Start = Button::new();
 
         start.padding = Spacing::all(1.0);
         start.size = Vec2::new(67.0, 30.0);
@@ -203,8 +222,8 @@ impl WindowRoot<WindowContext, Root> for Root {
             time.margin.top = 3.0;
             tray.content_mut().push(TrayElements::Text(time));
 
-            let mut timer = Timer::<WindowContext, CallbackImpls, NoID>::new();
-            timer.interval = 0.1;
+            let mut timer = Timer::<WindowContext, CallbackImpls, StaticID>::new("ClockTimer");
+            timer.interval = 1.0;
             timer.running = true;
             timer.repeat = true;
             tray.content_mut().push(TrayElements::Timer(timer));
@@ -242,8 +261,16 @@ impl ButtonCallbacks<WindowContext> for CallbackImpls {
 fn main() -> Result<(), Error> {
     let mut app = App::new();
     app.add_window(Root::default());
+    // Executed once the event loop runs
+    let _ = app.proxy().send_event(WindowContext::WatchIdle);
+
+    // `BAR_BACKEND=software` for machines without working GPU drivers
+    let backend = match std::env::var("BAR_BACKEND").as_deref() {
+        Ok("software") => Backend::Software,
+        _ => Backend::Gpu,
+    };
 
-    let mut event_loop = EventLoop::new(app)?;
+    let mut event_loop = EventLoop::with_backend(app, backend)?;
     event_loop.run()
 
     //let mut event_loop = toolkit::headless::HeadlessEventLoop::new(app);
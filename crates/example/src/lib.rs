@@ -24,7 +24,7 @@ pub enum WindowContext {
 impl Context for WindowContext {
     type Widget = Root;
     type WindowRoot = Root;
-    fn execute(&self, _: &mut ContentManager, tree: &mut Tree<Self>) {
+    fn execute(&self, _: &mut ContentManager, _tree: &mut Tree<Self>) {
         match self {
             WindowContext::RandomColor => {
                 //let button = tree
@@ -44,6 +44,7 @@ impl WindowRoot<WindowContext, Self> for Root {
     fn request(&self) -> WindowRequest {
         WindowRequest::new("desktop").desktop(DesktopOptions {
             title: "Test application".into(),
+            app_id: "pixaur.example".into(),
             resizable: true,
             decorations: false,
         })
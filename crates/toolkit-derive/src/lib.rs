@@ -229,13 +229,6 @@ pub fn derive_window_root_enum(input: TokenStream) -> TokenStream {
         }
     });
 
-    let arms_update = variants.iter().map(|v| {
-        let vname = &v.ident;
-        //quote! {
-        //    #enum_name::#vname(inner) => inner.update(gui, ctx)
-        //}
-    });
-
     let expanded = quote! {
         impl WindowRoot for #enum_name {
             type Gui = #gui_type;
@@ -264,11 +257,7 @@ pub fn derive_window_root_enum(input: TokenStream) -> TokenStream {
                 }
             }
 
-            fn update(&mut self, gui: &mut #gui_type, ctx: &toolkit::widget::FrameContext) {
-                //match self {
-                //    #(#arms_update),*
-                //}
-            }
+            fn update(&mut self, gui: &mut #gui_type, ctx: &toolkit::widget::FrameContext) {}
         }
     };
 
@@ -1,11 +1,17 @@
 use crate::{
     commands::CommandBuffer,
+    event::SystemEvent,
+    event_loop::{channel, EventProxy},
     rendering::Gpu,
     types::Bounds,
-    widget::{Context, FrameContext, Sender, Tree, Widget},
+    widget::{
+        ClipboardCommand, Context, ForeignToplevelCommand, FrameContext, IdleCommand, Sender, Tree,
+        Widget, WindowAction, WindowCommand,
+    },
     ContentManager, Error, WindowRoot,
 };
 use glam::Vec2;
+use wl_client::{foreign_toplevel::ForeignToplevel, output::Output};
 
 pub struct App<C, W, WR>
 where
@@ -14,9 +20,20 @@ where
     WR: WindowRoot<C, W>,
 {
     pub(crate) frontends: Vec<WR>,
+    /// Whether each of the `frontends` changed since it was last drawn.
+    pub(crate) redraw: Vec<bool>,
     pub(crate) requested_frontends: Vec<WR>,
+    pub(crate) outputs: Vec<Output>,
+    pub(crate) toplevels: Vec<ForeignToplevel>,
+    pub(crate) window_commands: Vec<WindowCommand<C>>,
+    pub(crate) clipboard_commands: Vec<ClipboardCommand>,
+    pub(crate) toplevel_commands: Vec<ForeignToplevelCommand>,
+    pub(crate) idle_commands: Vec<IdleCommand>,
+    /// Receives what the [proxies](App::proxy) send, until the event loop watches it.
+    pub(crate) proxy_channel: Option<channel::Channel<C>>,
 
     content: ContentManager,
+    proxy: channel::Sender<C>,
 
     _phantom0: std::marker::PhantomData<C>,
     _phantom1: std::marker::PhantomData<W>,
@@ -41,16 +58,31 @@ where
 {
     #[must_use]
     pub fn new() -> Self {
+        let (proxy, proxy_channel) = channel::channel();
         Self {
             frontends: vec![],
+            redraw: vec![],
             requested_frontends: vec![],
+            outputs: vec![],
+            toplevels: vec![],
+            window_commands: vec![],
+            clipboard_commands: vec![],
+            toplevel_commands: vec![],
+            idle_commands: vec![],
+            proxy_channel: Some(proxy_channel),
             content: ContentManager::default(),
+            proxy,
             _phantom0: std::marker::PhantomData,
             _phantom1: std::marker::PhantomData,
         }
     }
 
-    pub(crate) fn dispatch_queue(&mut self, gpu: &Gpu) -> Result<(), Error> {
+    pub(crate) fn dispatch_queue(&mut self, gpu: Option<&Gpu>) -> Result<(), Error> {
+        // Widgets may be waiting for the textures to show them
+        if self.content.has_requests() {
+            self.redraw.fill(true);
+        }
+
         self.content.dispatch_queue(gpu)
     }
 
@@ -58,6 +90,89 @@ where
         &mut self.content
     }
 
+    /// Lets other threads send contexts to the app, they are executed once the event loop
+    /// runs.
+    #[must_use]
+    pub fn proxy(&self) -> EventProxy<C> {
+        EventProxy::new(self.proxy.clone())
+    }
+
+    /// Connected outputs in the order the compositor advertised them.
+    #[must_use]
+    pub fn outputs(&self) -> &[Output] {
+        &self.outputs
+    }
+
+    /// Windows of every client, empty when the compositor doesn't list them.
+    #[must_use]
+    pub fn toplevels(&self) -> &[ForeignToplevel] {
+        &self.toplevels
+    }
+
+    /// Whether contexts queued requests the event loop applies after dispatching.
+    pub(crate) fn has_commands(&self) -> bool {
+        !self.window_commands.is_empty()
+            || !self.clipboard_commands.is_empty()
+            || !self.toplevel_commands.is_empty()
+            || !self.idle_commands.is_empty()
+    }
+
+    /// Returns `true` when the event was handled by a context.
+    pub(crate) fn handle_event(&mut self, event: &SystemEvent) -> bool {
+        match event {
+            SystemEvent::OutputAdded(output) | SystemEvent::OutputChanged(output) => {
+                match self.outputs.iter_mut().find(|o| o.name() == output.name()) {
+                    Some(existing) => *existing = output.clone(),
+                    None => self.outputs.push(output.clone()),
+                }
+            }
+            SystemEvent::OutputRemoved(output) => {
+                self.outputs.retain(|o| o.name() != output.name());
+            }
+            SystemEvent::ToplevelAdded(toplevel) => self.toplevels.push(toplevel.clone()),
+            SystemEvent::ToplevelChanged(toplevel) => {
+                let existing = self.toplevels.iter_mut().find(|t| t.id() == toplevel.id());
+                if let Some(existing) = existing {
+                    *existing = toplevel.clone();
+                }
+            }
+            SystemEvent::ToplevelRemoved(toplevel) => {
+                self.toplevels.retain(|t| t.id() != toplevel.id());
+            }
+            SystemEvent::CloseRequested(_)
+            | SystemEvent::Closed(_)
+            | SystemEvent::PopupDismissed(_)
+            | SystemEvent::StateChanged(_, _)
+            | SystemEvent::SelectionOffered(_, _)
+            | SystemEvent::SelectionReceived(_, _, _)
+            | SystemEvent::DragFinished(_)
+            | SystemEvent::DragCancelled
+            | SystemEvent::WorkspacesChanged(_)
+            | SystemEvent::SessionLocked
+            | SystemEvent::SessionLockFinished
+            | SystemEvent::Idled(_)
+            | SystemEvent::Resumed(_) => {}
+        }
+
+        let Some(ctx) = C::on_event(event) else {
+            return false;
+        };
+
+        self.execute(&ctx);
+        true
+    }
+
+    pub(crate) fn execute(&mut self, ctx: &C) {
+        ctx.execute(&mut self.content, &mut Tree {
+            frontends: self.frontends.as_mut_slice(),
+            commands: &mut self.window_commands,
+            clipboard: &mut self.clipboard_commands,
+            toplevels: &self.toplevels,
+            toplevel_commands: &mut self.toplevel_commands,
+            idle: &mut self.idle_commands,
+        });
+    }
+
     pub fn add_window(&mut self, mut window: WR) {
         window.setup(self);
         self.requested_frontends.push(window);
@@ -66,25 +181,39 @@ where
     pub(crate) fn tick_logic_frontend(
         &mut self,
         index: usize,
-        window_width: f32,
-        window_height: f32,
         frame: &FrameContext,
-    ) {
+    ) -> Vec<WindowAction> {
         let frontend = &mut self.frontends[index];
         let root = frontend.root_mut();
         let mut sender = Sender::<C>::default();
         root.update(frame, &mut sender);
-        root.layout(Bounds::new(
-            Vec2::ZERO,
-            Vec2::new(window_width, window_height),
-        ));
 
+        let actions = sender.take_actions();
         sender.execute(&mut self.content, Tree {
             frontends: self.frontends.as_mut_slice(),
+            commands: &mut self.window_commands,
+            clipboard: &mut self.clipboard_commands,
+            toplevels: &self.toplevels,
+            toplevel_commands: &mut self.toplevel_commands,
+            idle: &mut self.idle_commands,
         });
+
+        actions
+    }
+
+    pub(crate) fn tick_layout_frontend(
+        &mut self,
+        index: usize,
+        window_width: f32,
+        window_height: f32,
+    ) {
+        self.frontends[index].root_mut().layout(Bounds::new(
+            Vec2::ZERO,
+            Vec2::new(window_width, window_height),
+        ));
     }
 
-    pub(crate) fn tick_render_frontend(&mut self, index: usize) -> CommandBuffer {
+    pub(crate) fn tick_render_frontend(&mut self, index: usize) -> CommandBuffer<'_> {
         let frontend = &mut self.frontends[index];
         let root = frontend.root_mut();
         let mut commands = CommandBuffer::new(&self.content);
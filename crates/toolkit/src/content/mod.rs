@@ -2,10 +2,11 @@ mod svg;
 
 use crate::{
     content::svg::{SvgData, SvgRequest},
-    rendering::{material::Material, Gpu},
+    rendering::{material::Material, pixmap_from_bytes, Gpu},
     Error,
 };
 use fontdue::{Font, FontSettings};
+use resvg::tiny_skia::Pixmap;
 use std::{
     collections::HashMap,
     fs,
@@ -83,10 +84,16 @@ pub struct TextureHandle {
     id: usize,
 }
 
+/// Texture uploaded for the backend the event loop draws with.
+enum TextureData {
+    Gpu(Material),
+    Software(Pixmap),
+}
+
 #[derive(Default)]
 pub struct ContentManager {
     static_font: HashMap<String, Arc<Font>>,
-    static_textures: Vec<Material>,
+    static_textures: Vec<TextureData>,
     svg: Vec<SvgData>,
 
     queue: Vec<Request>,
@@ -163,28 +170,46 @@ impl ContentManager {
         svg_handle
     }
 
-    pub(crate) fn dispatch_queue(&mut self, gpu: &Gpu) -> Result<(), Error> {
+    /// Whether content was requested since the last [`ContentManager::dispatch_queue`].
+    pub(crate) fn has_requests(&self) -> bool {
+        !self.queue.is_empty()
+    }
+
+    /// Uploads the requested textures to `gpu`, or keeps their pixels for the software
+    /// renderer without one.
+    pub(crate) fn dispatch_queue(&mut self, gpu: Option<&Gpu>) -> Result<(), Error> {
         self.queue
             .drain(..)
             .try_for_each(|request| -> Result<(), Error> {
                 match request {
                     Request::Texture(texture_request) => {
-                        let material =
-                            Material::from_bytes(texture_request.bytes, &gpu.device, &gpu.queue)?;
+                        let texture = match gpu {
+                            Some(gpu) => TextureData::Gpu(Material::from_bytes(
+                                texture_request.bytes,
+                                &gpu.device,
+                                &gpu.queue,
+                            )?),
+                            None => {
+                                TextureData::Software(pixmap_from_bytes(texture_request.bytes)?)
+                            }
+                        };
                         if texture_request.is_static {
-                            self.static_textures.push(material);
+                            self.static_textures.push(texture);
                         } else {
                             todo!();
                         }
                     }
                     Request::Svg(svg_request) => {
-                        let material = Material::from_rgba_pixels(
-                            "svg",
-                            svg_request.pixmap.data(),
-                            (svg_request.width, svg_request.height),
-                            &gpu.device,
-                            &gpu.queue,
-                        );
+                        let texture = match gpu {
+                            Some(gpu) => TextureData::Gpu(Material::from_rgba_pixels(
+                                "svg",
+                                svg_request.pixmap.data(),
+                                (svg_request.width, svg_request.height),
+                                &gpu.device,
+                                &gpu.queue,
+                            )),
+                            None => TextureData::Software(svg_request.pixmap),
+                        };
                         self.svg.get_mut(svg_request.id).unwrap().textures.insert(
                             (svg_request.width, svg_request.height),
                             TextureHandle {
@@ -193,7 +218,7 @@ impl ContentManager {
                         );
 
                         if svg_request.is_static {
-                            self.static_textures.push(material);
+                            self.static_textures.push(texture);
                         } else {
                             todo!();
                         }
@@ -204,6 +229,20 @@ impl ContentManager {
     }
 
     pub(crate) fn get_texture(&self, handle: &Handle) -> &Material {
+        match self.get_texture_data(handle) {
+            TextureData::Gpu(material) => material,
+            TextureData::Software(_) => panic!("texture was dispatched without a gpu"),
+        }
+    }
+
+    pub(crate) fn get_pixmap(&self, handle: &Handle) -> &Pixmap {
+        match self.get_texture_data(handle) {
+            TextureData::Software(pixmap) => pixmap,
+            TextureData::Gpu(_) => panic!("texture was dispatched to the gpu"),
+        }
+    }
+
+    fn get_texture_data(&self, handle: &Handle) -> &TextureData {
         match handle {
             Handle::Texture(handle) => self.static_textures.get(handle.id).unwrap(),
             Handle::Svg(handle) => {
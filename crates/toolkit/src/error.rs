@@ -1,6 +1,6 @@
 use image::ImageError;
 use thiserror::Error;
-use wayland_client::{ConnectError, DispatchError};
+use wayland_client::{backend::WaylandError, ConnectError, DispatchError};
 use wgpu::{CreateSurfaceError, RequestAdapterError, RequestDeviceError, SurfaceError};
 
 #[derive(Error, Debug)]
@@ -10,6 +10,10 @@ pub enum Error {
     Connect(#[from] ConnectError),
     #[error("{0}")]
     Dispatch(#[from] DispatchError),
+    #[error("{0}")]
+    Wayland(#[from] WaylandError),
+    #[error("{0}")]
+    EventLoop(#[from] calloop::Error),
 
     //Wgpu
     #[error("{0}")]
@@ -28,6 +32,9 @@ pub enum Error {
     #[error("{0}")]
     Svg(#[from] resvg::usvg::Error),
 
+    #[error("Image has no pixels")]
+    EmptyImage,
+
     //Std
     #[error("{0}")]
     IO(#[from] std::io::Error),
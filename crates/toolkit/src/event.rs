@@ -0,0 +1,117 @@
+use std::time::Duration;
+use wl_client::{
+    clipboard::{ClipboardEvent, SelectionKind},
+    dnd::{DndAction, DragSourceEvent},
+    foreign_toplevel::{ForeignToplevel, ForeignToplevelEvent},
+    idle::IdleEvent,
+    output::{Output, OutputEvent},
+    session_lock::SessionLockEvent,
+    window::WindowState,
+    workspace::Workspace,
+};
+
+/// Event raised by the compositor, delivered through [`Context::on_event`](crate::widget::Context::on_event).
+#[derive(Debug, Clone, PartialEq)]
+pub enum SystemEvent {
+    OutputAdded(Output),
+    OutputChanged(Output),
+    OutputRemoved(Output),
+    /// The user asked to close the window with this id.
+    ///
+    /// The window is closed unless [`Context::on_event`](crate::widget::Context::on_event)
+    /// returns a context, which then decides with [`Tree::close_window`](crate::widget::Tree::close_window).
+    CloseRequested(String),
+    /// The window with this id was destroyed.
+    Closed(String),
+    /// The popup with this id was dismissed by the compositor and destroyed.
+    PopupDismissed(String),
+    /// The desktop window with this id was maximized, activated, tiled, etc.
+    StateChanged(String, WindowState),
+    /// The selection changed, with the MIME types it can be read as. Empty once cleared.
+    SelectionOffered(SelectionKind, Vec<String>),
+    /// Contents requested with [`Tree::read_selection`](crate::widget::Tree::read_selection),
+    /// with their MIME type.
+    SelectionReceived(SelectionKind, String, Vec<u8>),
+    /// A drag started with [`Sender::start_drag`](crate::widget::Sender::start_drag) was
+    /// dropped and read. With [`DndAction::Move`] the data should be removed from where it
+    /// was dragged.
+    DragFinished(DndAction),
+    /// A drag was dropped nowhere, rejected, or replaced by another drag.
+    DragCancelled,
+    /// A window of any client was opened, listed in [`Tree::toplevels`](crate::widget::Tree::toplevels).
+    ToplevelAdded(ForeignToplevel),
+    ToplevelChanged(ForeignToplevel),
+    ToplevelRemoved(ForeignToplevel),
+    /// Workspaces were added, removed, renamed or switched, with all of them in the order
+    /// the compositor listed them.
+    WorkspacesChanged(Vec<Workspace>),
+    /// The session is locked, only the [lock](crate::window::WindowRequest::lock) windows
+    /// are shown.
+    SessionLocked,
+    /// The compositor refused to lock the session or ended the lock, the lock windows should
+    /// be closed.
+    SessionLockFinished,
+    /// The user gave no input for a timeout watched with
+    /// [`Tree::watch_idle`](crate::widget::Tree::watch_idle).
+    Idled(Duration),
+    /// Input came back after [`SystemEvent::Idled`] with the same timeout.
+    Resumed(Duration),
+}
+
+impl From<OutputEvent> for SystemEvent {
+    fn from(value: OutputEvent) -> Self {
+        match value {
+            OutputEvent::Added(output) => Self::OutputAdded(output),
+            OutputEvent::Changed(output) => Self::OutputChanged(output),
+            OutputEvent::Removed(output) => Self::OutputRemoved(output),
+        }
+    }
+}
+
+impl From<ClipboardEvent> for SystemEvent {
+    fn from(value: ClipboardEvent) -> Self {
+        match value {
+            ClipboardEvent::Offered(kind, mime_types) => Self::SelectionOffered(kind, mime_types),
+            ClipboardEvent::Received(kind, mime_type, data) => {
+                Self::SelectionReceived(kind, mime_type, data)
+            }
+        }
+    }
+}
+
+impl From<DragSourceEvent> for SystemEvent {
+    fn from(value: DragSourceEvent) -> Self {
+        match value {
+            DragSourceEvent::Finished(action) => Self::DragFinished(action),
+            DragSourceEvent::Cancelled => Self::DragCancelled,
+        }
+    }
+}
+
+impl From<SessionLockEvent> for SystemEvent {
+    fn from(value: SessionLockEvent) -> Self {
+        match value {
+            SessionLockEvent::Locked => Self::SessionLocked,
+            SessionLockEvent::Finished => Self::SessionLockFinished,
+        }
+    }
+}
+
+impl From<IdleEvent> for SystemEvent {
+    fn from(value: IdleEvent) -> Self {
+        match value {
+            IdleEvent::Idled(timeout) => Self::Idled(timeout),
+            IdleEvent::Resumed(timeout) => Self::Resumed(timeout),
+        }
+    }
+}
+
+impl From<ForeignToplevelEvent> for SystemEvent {
+    fn from(value: ForeignToplevelEvent) -> Self {
+        match value {
+            ForeignToplevelEvent::Added(toplevel) => Self::ToplevelAdded(toplevel),
+            ForeignToplevelEvent::Changed(toplevel) => Self::ToplevelChanged(toplevel),
+            ForeignToplevelEvent::Removed(toplevel) => Self::ToplevelRemoved(toplevel),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Sources watched by [`EventLoop`](crate::EventLoop) next to the compositor connection,
+//! see [`EventLoop::insert_source`](crate::EventLoop::insert_source).
+
+pub use calloop::{
+    channel, generic, ping, timer, EventSource, Interest, Mode, PostAction, RegistrationToken,
+};
+
+use std::sync::mpsc::SendError;
+
+use crate::widget::Context;
+
+/// Collects the contexts created by the callbacks of event sources, executed once every
+/// source ready at the same time has been dispatched.
+pub struct Emitter<C: Context> {
+    inner: Vec<C>,
+}
+
+impl<C: Context> Default for Emitter<C> {
+    fn default() -> Self {
+        Self { inner: vec![] }
+    }
+}
+
+impl<C: Context> Emitter<C> {
+    pub fn create_event(&mut self, event: C) {
+        self.inner.push(event);
+    }
+
+    pub(crate) fn take_events(&mut self) -> Vec<C> {
+        std::mem::take(&mut self.inner)
+    }
+}
+
+/// Sends contexts to the app from other threads, e.g. for network or D-Bus replies.
+///
+/// Obtained from [`App::proxy`](crate::app::App::proxy). Each context wakes the event loop
+/// and is executed like the ones created by widgets.
+pub struct EventProxy<C: Context> {
+    sender: channel::Sender<C>,
+}
+
+impl<C: Context> Clone for EventProxy<C> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<C: Context> EventProxy<C> {
+    pub(crate) const fn new(sender: channel::Sender<C>) -> Self {
+        Self { sender }
+    }
+
+    /// Gives the context back when the event loop is gone.
+    pub fn send_event(&self, event: C) -> Result<(), SendError<C>> {
+        self.sender.send(event)
+    }
+}
@@ -23,6 +23,7 @@ where
     pub fn new(mut app: App<C, W, WR>) -> Self {
         let mut windows = std::mem::take(&mut app.requested_frontends);
         windows.iter_mut().for_each(|f| f.setup(&mut app));
+        app.redraw = vec![true; windows.len()];
         app.frontends = windows;
         Self { app }
     }
@@ -30,7 +31,8 @@ where
     pub fn run_logic(&mut self) {
         let frame = FrameContext::default();
         for i in 0..self.app.frontends.len() {
-            self.app.tick_logic_frontend(i, 1920.0, 1080.0, &frame);
+            self.app.tick_logic_frontend(i, &frame);
+            self.app.tick_layout_frontend(i, 1920.0, 1080.0);
         }
     }
 
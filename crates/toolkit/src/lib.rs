@@ -22,25 +22,54 @@ pub use fontdue;
 pub use glam;
 
 pub mod app;
+pub mod event;
 pub mod event_loop;
 pub mod widget;
 pub mod window;
 
 use crate::{
     app::App,
-    rendering::{commands::CommandBuffer, Gpu, Renderer},
-    widget::{Context, FrameContext, Widget},
-    window::{Window, WindowPointer, WindowRequest},
+    event::SystemEvent,
+    event_loop::{channel, Emitter, EventSource, RegistrationToken},
+    rendering::{Gpu, Renderer, SoftwareRenderer},
+    widget::{
+        ClipboardCommand, Context, ForeignToplevelCommand, FrameContext, IdleCommand,
+        ToplevelAction, Widget, WindowAction, WindowCommand,
+    },
+    window::{Window, WindowPointer, WindowRenderer, WindowRequest},
 };
+use calloop_wayland_source::WaylandSource;
 pub use error::*;
-pub use rendering::commands;
-use std::{ffi::c_void, ptr::NonNull, sync::Arc, time::Instant};
-use wayland_client::{Connection, EventQueue, Proxy};
+pub use rendering::{commands, Backend};
+use std::{
+    cell::RefCell,
+    ffi::c_void,
+    ptr::NonNull,
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use wayland_client::{Connection, Proxy, QueueHandle};
 pub use wl_client::window::TargetMonitor;
-use wl_client::{window::WindowLayer, WlClient};
+use wl_client::{
+    output::OutputEvent,
+    window::{WindowEvent, WindowLayer},
+    WlClient,
+};
 pub use wl_client::{
-    window::{DesktopOptions, SpecialOptions},
-    Anchor,
+    clipboard::{SelectionKind, TEXT_MIME_TYPES},
+    dnd::{DndAction, DragIcon, DragOffer, DroppedData},
+    foreign_toplevel::{ForeignToplevel, ForeignToplevelState},
+    keyboard::{KeyEvent, KeyState, Keysym, Modifiers},
+    output::Output,
+    text_input::TextInputEvent,
+    touch::{TouchPhase, TouchPoint},
+    window::{
+        DesktopOptions, Margin, PopupAnchor, PopupGravity, PopupOptions, SpecialOptions,
+        WindowState,
+    },
+    workspace::{Workspace, WorkspaceGroup, WorkspaceState},
+    Anchor, CursorShape, KeyboardInteractivity, ResizeEdge, Scroll, ScrollSource,
 };
 
 pub struct EventLoop<C, W, WR>
@@ -51,12 +80,19 @@ where
 {
     app: App<C, W, WR>,
     windows: Vec<Window>,
+    /// Requests targeting [`TargetMonitor::All`], instantiated again on every new output.
+    broadcasts: Vec<WindowRequest>,
 
     client: WlClient,
-    event_queue: EventQueue<WlClient>,
+    conn: Connection,
+    qh: QueueHandle<WlClient>,
     display_ptr: NonNull<c_void>,
+    /// Polls the compositor connection together with the sources added by the app.
+    event_loop: calloop::EventLoop<'static, WlClient>,
+    emitter: Rc<RefCell<Emitter<C>>>,
 
-    gpu: Gpu,
+    /// `None` with [`Backend::Software`].
+    gpu: Option<Gpu>,
 }
 
 impl<C, W, WR> EventLoop<C, W, WR>
@@ -65,7 +101,13 @@ where
     W: Widget<C>,
     WR: WindowRoot<C, W>,
 {
+    /// Connects to the compositor and draws the windows with [`Backend::Gpu`].
     pub fn new(app: App<C, W, WR>) -> Result<Self, Error> {
+        Self::with_backend(app, Backend::default())
+    }
+
+    /// Connects to the compositor and draws the windows with `backend`.
+    pub fn with_backend(mut app: App<C, W, WR>, backend: Backend) -> Result<Self, Error> {
         let conn = Connection::connect_to_env()?;
 
         let display = conn.display();
@@ -79,11 +121,19 @@ where
         event_queue.roundtrip(&mut client)?; //Register objects
         event_queue.roundtrip(&mut client)?; //Register outputs
 
+        let display_ptr = NonNull::new(display.id().as_ptr().cast::<c_void>())
+            .ok_or(Error::DisplayNullPointer)?;
+
         //Fix egl error: BadDisplay
-        let (display_ptr, gpu) = {
-            let display_ptr = NonNull::new(display.id().as_ptr().cast::<c_void>())
-                .ok_or(Error::DisplayNullPointer)?;
-            let dummy = client.create_window_backend(qh, "dummy", 1, 1, WindowLayer::default());
+        let gpu = if backend == Backend::Gpu {
+            let dummy = client.create_window_backend(
+                qh.clone(),
+                "dummy",
+                1,
+                1,
+                WindowLayer::default(),
+                None,
+            );
             event_queue.roundtrip(&mut client)?; //Init dummy
 
             let dummy_ptr = dummy
@@ -98,85 +148,551 @@ where
             client.destroy_window_backend("dummy");
             event_queue.roundtrip(&mut client)?; //Destroy dummy
 
-            (display_ptr, gpu)
+            Some(gpu)
+        } else {
+            None
         };
 
+        let event_loop = calloop::EventLoop::try_new()?;
+        WaylandSource::new(conn.clone(), event_queue)
+            .insert(event_loop.handle())
+            .map_err(|e| e.error)?;
+        client.insert_sources(&event_loop.handle())?;
+
+        let emitter = Rc::new(RefCell::new(Emitter::default()));
+        if let Some(channel) = app.proxy_channel.take() {
+            let emitter = emitter.clone();
+            event_loop
+                .handle()
+                .insert_source(channel, move |event, (), _| {
+                    if let channel::Event::Msg(ctx) = event {
+                        emitter.borrow_mut().create_event(ctx);
+                    }
+                })
+                .map_err(|e| e.error)?;
+        }
+
         Ok(Self {
             app,
             windows: vec![],
+            broadcasts: vec![],
 
             client,
-            event_queue,
+            conn,
+            qh,
             display_ptr,
+            event_loop,
+            emitter,
 
             gpu,
         })
     }
 
+    /// Watches `source` while the loop runs, e.g. a [`timer`](event_loop::timer), a
+    /// [`channel`](event_loop::channel) or a file descriptor with [`generic`](event_loop::generic).
+    ///
+    /// The contexts `callback` creates are executed right after it is called.
+    pub fn insert_source<S, F>(
+        &mut self,
+        source: S,
+        mut callback: F,
+    ) -> Result<RegistrationToken, Error>
+    where
+        S: EventSource + 'static,
+        F: FnMut(S::Event, &mut S::Metadata, &mut Emitter<C>) -> S::Ret + 'static,
+    {
+        let emitter = self.emitter.clone();
+        let token = self
+            .event_loop
+            .handle()
+            .insert_source(source, move |event, metadata, _| {
+                callback(event, metadata, &mut emitter.borrow_mut())
+            })
+            .map_err(|e| e.error)?;
+
+        Ok(token)
+    }
+
+    /// Stops watching and drops a source added with [`EventLoop::insert_source`].
+    pub fn remove_source(&mut self, token: RegistrationToken) {
+        self.event_loop.handle().remove(token);
+    }
+
     pub fn run(&mut self) -> Result<(), Error> {
+        self.handle_output_events()?;
         self.init_windows_backends()?;
 
         let mut previous = Instant::now();
-        let mut frame = FrameContext::default();
 
         loop {
+            // Last window closed, windows waiting for their monitor keep the loop alive, as do
+            // the ones shown on every monitor, recreated when one is connected
+            if self.windows.is_empty()
+                && self.app.requested_frontends.is_empty()
+                && self.broadcasts.is_empty()
+            {
+                return Ok(());
+            }
+
             let current = Instant::now();
             let delta = current - previous;
             previous = current;
 
-            frame.delta_time = delta.as_secs_f64();
-            frame.position = self.client.pointer().position();
-            frame.buttons = self.client.pointer().buttons();
+            let mut scroll = self.client.pointer_mut().take_scroll();
+            let mut keys = self.client.keyboard_mut().take_events();
+            let mut touches = self.client.touch_mut().take_points();
+            let mut drops = self.client.dnd_mut().take_drops();
+            let mut text_input = self.client.text_input_mut().take_events();
+            let workspaces: Vec<_> = self.client.workspaces().iter().cloned().collect();
 
-            self.app.dispatch_queue(&self.gpu)?;
+            self.app.dispatch_queue(self.gpu.as_ref())?;
 
+            let mut close_requests = vec![];
+            let mut drags = vec![];
+            // Held keys repeat without any event from the compositor
+            let mut deadline: Option<Instant> = self.client.keyboard().next_repeat();
             for (i, window) in self.windows.iter_mut().enumerate() {
                 let mut backend = window
                     .backend
                     .lock()
                     .map_err(|e| Error::LockFailed(e.to_string()))?;
                 if backend.can_resize() {
-                    window.configuration.width = backend
-                        .width
-                        .try_into()
-                        .map_err(|_| Error::NegativeWidth(backend.width))?;
-
-                    window.configuration.height = backend
-                        .height
-                        .try_into()
-                        .map_err(|_| Error::NegativeHeight(backend.height))?;
-
-                    self.gpu
-                        .confugure_surface(&window.surface, &window.configuration);
+                    window.renderer.resize(self.gpu.as_ref(), &mut backend)?;
+                    backend.apply_scale();
                     backend.set_resized();
+                    self.app.redraw[i] = true;
                 }
 
-                self.app.tick_logic_frontend(
-                    i,
-                    window.configuration.width as f32,
-                    window.configuration.height as f32,
-                    &frame,
-                );
+                // The compositor waits for a frame following the configure
+                if backend.take_configured() {
+                    self.app.redraw[i] = true;
+                }
+
+                let width = backend.width as f32;
+                let height = backend.height as f32;
+                let scale = backend.scale_factor() as f32;
+
+                let mut frame = FrameContext {
+                    delta_time: delta.as_secs_f64(),
+                    modifiers: self.client.keyboard().modifiers(),
+                    window_state: backend.state(),
+                    workspaces: workspaces.clone(),
+                    ..FrameContext::default()
+                };
+
+                let pointer = self.client.pointer();
+                let hovered = pointer.focus() == Some(&backend.id);
+                if hovered {
+                    frame.position = pointer.position();
+                    frame.buttons = pointer.buttons();
+                    frame.scroll = std::mem::take(&mut scroll);
+                }
 
-                backend.frame();
-                if !backend.can_draw() {
+                (frame.touches, touches) = touches
+                    .into_iter()
+                    .partition(|point| *point.window() == backend.id);
+
+                if self.client.keyboard().focus() == Some(&backend.id) {
+                    frame.focused = true;
+                    frame.keys = std::mem::take(&mut keys);
+                }
+
+                let text_focused = self.client.text_input().focus() == Some(&backend.id);
+                if text_focused {
+                    frame.text_input = std::mem::take(&mut text_input);
+                }
+
+                frame.drag = self
+                    .client
+                    .dnd()
+                    .current()
+                    .filter(|drag| *drag.window() == backend.id)
+                    .cloned();
+
+                (frame.drops, drops) = drops
+                    .into_iter()
+                    .partition(|drop| *drop.window() == backend.id);
+
+                let mut cursor = CursorShape::Default;
+                let mut accepted = None;
+                let mut text_cursor = None;
+                for action in self.app.tick_logic_frontend(i, &frame) {
+                    let grab = self.client.grab();
+                    match (action, grab) {
+                        (WindowAction::Move, Some((seat, serial))) => {
+                            backend.start_move(seat, serial);
+                        }
+                        (WindowAction::Resize(edge), Some((seat, serial))) => {
+                            backend.start_resize(seat, serial, edge);
+                        }
+                        (WindowAction::ShowMenu(position), Some((seat, serial))) => {
+                            backend.show_window_menu(seat, serial, position);
+                        }
+                        (WindowAction::SetCursor(shape), _) => cursor = shape,
+                        (WindowAction::AcceptDrag(mime_type), _) => accepted = Some(mime_type),
+                        (WindowAction::StartDrag(mime_types, data, icon), _) => {
+                            drags.push((backend.id.clone(), mime_types, data, icon));
+                        }
+                        (WindowAction::TextInput(cursor), _) => text_cursor = Some(cursor),
+                        (WindowAction::Redraw, _) => self.app.redraw[i] = true,
+                        (WindowAction::WakeAt(at), _) => {
+                            deadline = Some(deadline.map_or(at, |d| d.min(at)));
+                        }
+                        (WindowAction::Toplevel(action), _) => {
+                            apply_toplevel_action(&mut backend, action);
+                        }
+                        (WindowAction::ActivateWorkspace(id), _) => {
+                            self.client.activate_workspace(id);
+                        }
+                        (WindowAction::Close, _) => close_requests.push(backend.id.clone()),
+                        // No seat to tie the request to
+                        _ => {}
+                    }
+                }
+
+                if hovered {
+                    self.client.set_cursor(cursor);
+                }
+
+                if frame.drag.is_some() {
+                    self.client.accept_drag(accepted.as_deref());
+                }
+
+                if text_focused {
+                    match text_cursor {
+                        Some(cursor) => self
+                            .client
+                            .enable_text_input(cursor.position.as_ivec2(), cursor.size.as_ivec2()),
+                        None => self.client.disable_text_input(),
+                    }
+                }
+
+                // Redrawn once the compositor has shown the previous frame
+                if !self.app.redraw[i] || !backend.can_draw() {
                     continue;
-                    //return Ok(());
                 }
 
+                self.app.redraw[i] = false;
+                self.app.tick_layout_frontend(i, width, height);
                 let mut commands = self.app.tick_render_frontend(i);
-                window.renderer.render(
-                    &self.gpu,
-                    &window.surface,
-                    &mut commands,
-                    window.configuration.width as f32,
-                    window.configuration.height as f32,
-                )?;
-                backend.commit();
+                window
+                    .renderer
+                    .render(self.gpu.as_ref(), &mut backend, &mut commands, scale)?;
+            }
+
+            for id in close_requests {
+                self.request_close(&id);
+            }
+
+            // Starting a drag locks the window it starts from
+            for (id, mime_types, data, icon) in drags {
+                self.client
+                    .start_drag(&self.qh, &id, &mime_types, data, icon.as_ref());
+            }
+
+            // Windows changed by the ones updated after them can be drawn right away and
+            // commands from widgets applied, otherwise sleeps until the compositor or a source
+            // wakes the loop, or a widget deadline or key repeat
+            let ready = self.app.has_commands()
+                || self
+                    .windows
+                    .iter()
+                    .zip(&self.app.redraw)
+                    .any(|(window, redraw)| {
+                        *redraw && window.backend.lock().is_ok_and(|b| b.can_draw())
+                    });
+            let timeout = if ready {
+                Some(Duration::ZERO)
+            } else {
+                deadline.map(|d| d.saturating_duration_since(Instant::now()))
+            };
+            self.event_loop.dispatch(timeout, &mut self.client)?;
+            let contexts = self.emitter.borrow_mut().take_events();
+            for ctx in contexts {
+                self.app.execute(&ctx);
+            }
+
+            self.handle_output_events()?;
+            self.handle_window_events();
+            self.handle_clipboard();
+            self.handle_drag_events();
+            self.handle_foreign_toplevels();
+            self.handle_workspaces();
+            self.handle_session_lock();
+            self.handle_window_commands()?;
+            // Windows opened by the same contexts exist by now
+            self.handle_idle();
+        }
+    }
+
+    fn handle_window_events(&mut self) {
+        for event in self.client.take_window_events() {
+            match event {
+                WindowEvent::CloseRequested(id) => self.request_close(&id),
+                WindowEvent::Closed(id) => {
+                    // Already removed, e.g. together with its output
+                    let Some(index) = self.windows.iter().position(|w| w.id() == id) else {
+                        continue;
+                    };
+
+                    self.destroy_window(index);
+                    self.app.handle_event(&SystemEvent::Closed(id.to_string()));
+                }
+                WindowEvent::StateChanged(id, state) => {
+                    self.app
+                        .handle_event(&SystemEvent::StateChanged(id.to_string(), state));
+                }
+                WindowEvent::PopupDone(id) => {
+                    let Some(index) = self.windows.iter().position(|w| w.id() == id) else {
+                        continue;
+                    };
+
+                    self.destroy_window(index);
+                    self.app
+                        .handle_event(&SystemEvent::PopupDismissed(id.to_string()));
+                }
+            }
+        }
+    }
+
+    /// Closes the window unless a context handles [`SystemEvent::CloseRequested`].
+    fn request_close(&mut self, id: &str) {
+        let event = SystemEvent::CloseRequested(id.to_string());
+        if !self.app.handle_event(&event) {
+            self.app
+                .window_commands
+                .push(WindowCommand::Close(id.to_string()));
+        }
+    }
+
+    fn handle_clipboard(&mut self) {
+        for event in self.client.clipboard_mut().take_events() {
+            self.app.handle_event(&event.into());
+        }
+
+        for command in std::mem::take(&mut self.app.clipboard_commands) {
+            match command {
+                ClipboardCommand::Set(kind, mime_types, data) => {
+                    self.client.set_selection(&self.qh, kind, &mime_types, data);
+                }
+                ClipboardCommand::Clear(kind) => self.client.clear_selection(kind),
+                ClipboardCommand::Read(kind, Some(mime_type)) => {
+                    self.client.read_selection(kind, &mime_type);
+                }
+                ClipboardCommand::Read(kind, None) => {
+                    let offered = self.client.clipboard().mime_types(kind);
+                    if let Some(mime_type) = TEXT_MIME_TYPES
+                        .into_iter()
+                        .find(|m| offered.iter().any(|o| o == m))
+                    {
+                        self.client.read_selection(kind, mime_type);
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_drag_events(&mut self) {
+        for event in self.client.dnd_mut().take_events() {
+            self.app.handle_event(&event.into());
+        }
+    }
+
+    fn handle_foreign_toplevels(&mut self) {
+        for event in self.client.foreign_toplevels_mut().take_events() {
+            self.app.handle_event(&event.into());
+        }
+
+        for command in std::mem::take(&mut self.app.toplevel_commands) {
+            match command {
+                ForeignToplevelCommand::Activate(id) => self.client.activate_toplevel(id),
+                ForeignToplevelCommand::SetMinimized(id, minimized) => {
+                    self.client.set_toplevel_minimized(id, minimized);
+                }
+                ForeignToplevelCommand::SetMaximized(id, maximized) => {
+                    self.client.set_toplevel_maximized(id, maximized);
+                }
+                ForeignToplevelCommand::Close(id) => self.client.close_toplevel(id),
+            }
+        }
+    }
+
+    fn handle_workspaces(&mut self) {
+        if self.client.workspaces_mut().take_changed() {
+            let workspaces = self.client.workspaces().iter().cloned().collect();
+            self.app
+                .handle_event(&SystemEvent::WorkspacesChanged(workspaces));
+        }
+    }
+
+    fn handle_session_lock(&mut self) {
+        for event in self.client.session_lock_mut().take_events() {
+            self.app.handle_event(&event.into());
+        }
+    }
+
+    fn handle_idle(&mut self) {
+        for event in self.client.idle_mut().take_events() {
+            self.app.handle_event(&event.into());
+        }
+
+        for command in std::mem::take(&mut self.app.idle_commands) {
+            match command {
+                IdleCommand::Inhibit(id, inhibited) => {
+                    self.client.set_idle_inhibited(&self.qh, &id, inhibited);
+                }
+                IdleCommand::Watch(timeout) => self.client.watch_idle(&self.qh, timeout),
+                IdleCommand::Unwatch(timeout) => self.client.unwatch_idle(timeout),
+            }
+        }
+    }
+
+    /// Closes the lock windows on every output, then unlocks.
+    fn unlock_session(&mut self) -> Result<(), Error> {
+        while let Some(index) = self.windows.iter().position(Window::is_lock) {
+            let id = self.windows[index].id();
+            self.destroy_window(index);
+            self.app.handle_event(&SystemEvent::Closed(id.to_string()));
+        }
+
+        let is_lock = |layer: &WindowLayer| matches!(layer, WindowLayer::Lock);
+        self.broadcasts.retain(|request| !is_lock(&request.layer));
+        self.app
+            .requested_frontends
+            .retain(|frontend| !is_lock(&frontend.request().layer));
+
+        // The compositor must receive the unlock even if the app exits right after
+        self.client.unlock_session();
+        self.conn.roundtrip()?;
+        Ok(())
+    }
+
+    fn handle_window_commands(&mut self) -> Result<(), Error> {
+        let commands = std::mem::take(&mut self.app.window_commands);
+        if commands.is_empty() {
+            return Ok(());
+        }
+
+        for command in commands {
+            match command {
+                WindowCommand::Open(root) => self.app.add_window(root),
+                WindowCommand::Close(id) => {
+                    let instance = format!("{id}@");
+                    while let Some(index) = self.windows.iter().position(|w| {
+                        let window_id = w.id();
+                        *window_id == id || window_id.starts_with(&instance)
+                    }) {
+                        let window_id = self.windows[index].id();
+                        self.destroy_window(index);
+                        self.app
+                            .handle_event(&SystemEvent::Closed(window_id.to_string()));
+                    }
+
+                    self.broadcasts.retain(|request| request.id != id);
+                    self.app
+                        .requested_frontends
+                        .retain(|frontend| frontend.request().id != id);
+                }
+                WindowCommand::Toplevel(id, action) => {
+                    let Some(index) = self.windows.iter().position(|w| *w.id() == id) else {
+                        continue;
+                    };
+
+                    let mut backend = self.windows[index]
+                        .backend
+                        .lock()
+                        .map_err(|e| Error::LockFailed(e.to_string()))?;
+                    apply_toplevel_action(&mut backend, action);
+                    // Size limits are double-buffered state, applied on the next commit
+                    self.app.redraw[index] = true;
+                }
+                WindowCommand::UnlockSession => self.unlock_session()?,
+            }
+        }
+
+        self.init_windows_backends()
+    }
+
+    fn handle_output_events(&mut self) -> Result<(), Error> {
+        let events = self.client.outputs_mut().take_events();
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let mut added = false;
+        for event in events {
+            match &event {
+                OutputEvent::Added(output) => {
+                    added = true;
+                    for request in self.broadcasts.clone() {
+                        let mut frontend = WR::default();
+                        frontend.setup(&mut self.app);
+
+                        let id = format!("{}@{}", request.id, output.name());
+                        self.create_window(frontend, id, &request, Some(output.name()))?;
+                    }
+                }
+                OutputEvent::Removed(output) => self.remove_output_windows(output.name()),
+                OutputEvent::Changed(_) => {}
+            }
+
+            self.app.handle_event(&event.into());
+        }
+
+        // Monitors requested by name or index may have just been connected
+        if added {
+            self.init_windows_backends()?;
+        }
+
+        Ok(())
+    }
+
+    /// Destroys the windows shown on a disconnected output.
+    ///
+    /// Roots targeting a specific monitor are kept pending until it is connected again,
+    /// the ones created for [`TargetMonitor::All`] are dropped and recreated from their
+    /// request on every monitor connected later.
+    fn remove_output_windows(&mut self, output: &str) {
+        let mut i = 0;
+        while i < self.windows.len() {
+            if self.windows[i].output.as_deref() != Some(output) {
+                i += 1;
+                continue;
+            }
+
+            let frontend = self.destroy_window(i);
+            let request = frontend.request();
+            if !matches!(request.layer.target(), Some(TargetMonitor::All)) {
+                self.app.requested_frontends.push(frontend);
             }
+        }
+    }
 
-            self.event_queue.blocking_dispatch(&mut self.client)?;
+    fn destroy_window(&mut self, index: usize) -> WR {
+        // Popups must be destroyed before the window they are attached to
+        let parent = self.windows[index].id();
+        while let Some(child) = self
+            .windows
+            .iter()
+            .position(|w| w.parent().as_deref() == Some(parent.as_str()))
+        {
+            let child_id = self.windows[child].id();
+            self.destroy_window(child);
+            self.app
+                .handle_event(&SystemEvent::Closed(child_id.to_string()));
         }
+
+        let index = self.windows.iter().position(|w| w.id() == parent).unwrap();
+        let window = self.windows.remove(index);
+        let frontend = self.app.frontends.remove(index);
+        self.app.redraw.remove(index);
+
+        // The wgpu surface and renderer must go before the wayland surface they draw to
+        let id = window.id();
+        drop(window);
+        self.client.destroy_window_backend(&id);
+
+        frontend
     }
 
     fn init_windows_backends(&mut self) -> Result<(), Error> {
@@ -185,41 +701,147 @@ where
         }
 
         let requests = std::mem::take(&mut self.app.requested_frontends);
-        let qh = self.event_queue.handle();
-        requests.into_iter().try_for_each(|frontend| {
-            let request = frontend.request();
-            let backend = self.client.create_window_backend(
-                qh.clone(),
-                request.id,
-                request.width,
-                request.height,
-                request.layer,
-            );
+        for frontend in requests {
+            let mut request = frontend.request();
+
+            if let WindowLayer::Popup(options) = &mut request.layer {
+                // Popup opened before its parent window
+                let Some(parent) = self.popup_parent(&options.parent) else {
+                    self.app.requested_frontends.push(frontend);
+                    continue;
+                };
 
-            let (width, height, surface_ptr) = {
-                let guard = backend.lock().unwrap();
+                options.parent = parent;
+            }
 
-                let width: u32 = guard.width.try_into().expect("width must be >= 0");
-                let height: u32 = guard.height.try_into().expect("height must be >= 0");
-                (width, height, guard.as_ptr())
-            };
+            let target = request.layer.target();
+            let outputs = target.map_or_else(|| vec![None], |t| self.client.target_outputs(t));
+
+            // Requested monitor is not connected
+            if outputs.is_empty() {
+                self.app.requested_frontends.push(frontend);
+                continue;
+            }
 
-            let window_ptr = WindowPointer::new(self.display_ptr, surface_ptr);
-            let (surface, configuration) = self.gpu.create_surface(window_ptr, width, height)?;
-            let renderer = Renderer::new(&self.gpu, None, &surface)?;
-            let window = Window::new(backend, surface, configuration, renderer);
+            if let Some(TargetMonitor::All) = target {
+                self.broadcasts.push(request.clone());
+            }
 
-            self.windows.push(window);
-            self.app.frontends.push(frontend);
+            let mut frontend = Some(frontend);
+            for output in outputs {
+                let frontend = frontend.take().unwrap_or_else(|| {
+                    let mut frontend = WR::default();
+                    frontend.setup(&mut self.app);
+                    frontend
+                });
 
-            Ok::<(), Error>(())
-        })?;
+                let id = match (target, &output) {
+                    (Some(TargetMonitor::All), Some(output)) => format!("{}@{output}", request.id),
+                    _ => request.id.clone(),
+                };
+
+                self.create_window(frontend, id, &request, output.as_deref())?;
+            }
+        }
 
         Ok(())
     }
+
+    /// Window a popup requested on `parent` is attached to.
+    ///
+    /// Roots shown on every monitor have an instance per output, the popup goes on the one the
+    /// pointer or keyboard is in, i.e. where it was most likely opened from, or the first one.
+    fn popup_parent(&self, parent: &str) -> Option<String> {
+        if self.client.window(parent).is_some() {
+            return Some(parent.to_string());
+        }
+
+        let instance = format!("{parent}@");
+        let focused = [
+            self.client.pointer().focus(),
+            self.client.keyboard().focus(),
+        ];
+        focused
+            .into_iter()
+            .flatten()
+            .cloned()
+            .chain(self.windows.iter().map(Window::id))
+            .find(|id| id.starts_with(&instance))
+            .map(|id| id.to_string())
+    }
+
+    fn create_window(
+        &mut self,
+        frontend: WR,
+        id: String,
+        request: &WindowRequest,
+        output: Option<&str>,
+    ) -> Result<(), Error> {
+        let backend = self.client.create_window_backend(
+            self.qh.clone(),
+            id,
+            request.width,
+            request.height,
+            request.layer.clone(),
+            output,
+        );
+
+        let (width, height, surface_ptr) = {
+            let guard = backend.lock().unwrap();
+
+            let (width, height) = guard.physical_size();
+            let width: u32 = width.try_into().expect("width must be >= 0");
+            let height: u32 = height.try_into().expect("height must be >= 0");
+            (width, height, guard.as_ptr())
+        };
+
+        let renderer = match &self.gpu {
+            Some(gpu) => {
+                let window_ptr = WindowPointer::new(self.display_ptr, surface_ptr);
+                let (surface, configuration) = gpu.create_surface(window_ptr, width, height)?;
+                let renderer = Renderer::new(gpu, None, &surface)?;
+                backend
+                    .lock()
+                    .map_err(|e| Error::LockFailed(e.to_string()))?
+                    .set_gpu_rendered();
+                WindowRenderer::Gpu {
+                    surface,
+                    configuration,
+                    renderer,
+                }
+            }
+            None => WindowRenderer::Software(SoftwareRenderer::default()),
+        };
+        let window = Window::new(backend, renderer, output.map(ToString::to_string));
+
+        self.windows.push(window);
+        self.app.frontends.push(frontend);
+        self.app.redraw.push(true);
+
+        Ok(())
+    }
+}
+
+fn apply_toplevel_action(backend: &mut wl_client::window::Window, action: ToplevelAction) {
+    match action {
+        ToplevelAction::SetTitle(title) => backend.set_title(title),
+        ToplevelAction::SetMaximized(maximized) => backend.set_maximized(maximized),
+        ToplevelAction::Minimize => backend.set_minimized(),
+        ToplevelAction::SetFullscreen(fullscreen) => backend.set_fullscreen(fullscreen),
+        ToplevelAction::SetMinSize(width, height) => {
+            backend.set_min_size(width as i32, height as i32);
+        }
+        ToplevelAction::SetMaxSize(width, height) => {
+            backend.set_max_size(width as i32, height as i32);
+        }
+    }
 }
 
-pub trait WindowRoot<C: Context, W: Widget<C>>: Sized 
+/// Root of a window's widget tree.
+///
+/// `Default` is used to instantiate additional roots when one request maps to several surfaces,
+/// e.g. a layer window targeting [`TargetMonitor::All`].
+pub trait WindowRoot<C: Context, W: Widget<C>>: Sized + Default
 where
     C: Context<Widget = W, WindowRoot = Self>,
     W: Widget<C>,
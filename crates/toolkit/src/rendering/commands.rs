@@ -22,9 +22,9 @@ pub(crate) trait DrawDispatcher {
 }
 
 pub struct DrawRectCommand {
-    rect: Bounds,
-    color: Color,
-    stroke: Stroke,
+    pub(crate) rect: Bounds,
+    pub(crate) color: Color,
+    pub(crate) stroke: Stroke,
 }
 
 impl DrawRectCommand {
@@ -56,8 +56,8 @@ impl DrawDispatcher for DrawRectCommand {
         ];
         pipeline.buffer_pool.push(InstanceData::new_uv_2(
             UV,
-            self.rect.position.round(),
-            self.rect.size.round(),
+            pipeline.snap(self.rect.position),
+            pipeline.snap(self.rect.size),
             &self.color,
             Some(self.stroke.clone()),
             pipeline.projection,
@@ -70,9 +70,9 @@ impl DrawDispatcher for DrawRectCommand {
 }
 
 pub struct DrawTextureCommand {
-    rect: Bounds,
-    texture: Texture,
-    stroke: Stroke,
+    pub(crate) rect: Bounds,
+    pub(crate) texture: Texture,
+    pub(crate) stroke: Stroke,
 }
 
 impl DrawTextureCommand {
@@ -120,11 +120,11 @@ impl DrawDispatcher for DrawTextureCommand {
 }
 
 pub struct DrawTextCommand<'frame> {
-    size: u32,
-    color: Color,
-    position: Vec2,
-    font: &'frame FontHandle,
-    layout: &'frame Layout,
+    pub(crate) size: u32,
+    pub(crate) color: Color,
+    pub(crate) position: Vec2,
+    pub(crate) font: &'frame FontHandle,
+    pub(crate) layout: &'frame Layout,
 }
 
 impl<'frame> DrawTextCommand<'frame> {
@@ -153,25 +153,22 @@ impl DrawDispatcher for DrawTextCommand<'_> {
             .fonts
             .entry(self.font.inner.name().unwrap().to_string())
             .or_default();
-        let atlas = set.get_atlas(self.size);
+        // Glyphs are rasterized in buffer pixels and drawn at their surface size
+        let size = (self.size as f32 * pipeline.scale).round() as u32;
+        let scale = size as f32 / self.size as f32;
+        let atlas = set.get_atlas(size);
 
         self.layout.glyphs().iter().for_each(|glyph| {
-            match glyph.parent {
-                ' ' | '\t' | '\n' | '\r' | '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{FEFF}' => {
-                    return;
-                }
-                c if c.is_control() => return,
-                _ => {}
+            if is_invisible(glyph.parent) {
+                return;
             }
 
-            let data = atlas.get_or_add_glyph(glyph.parent, self.size, &self.font.inner);
+            let data = atlas.get_or_add_glyph(glyph.parent, size, &self.font.inner);
+            let position = (self.position + Vec2::new(glyph.x, glyph.y)) * pipeline.scale;
             pipeline.buffer_pool.push(InstanceData::new_uv_4(
                 data.uv,
-                Vec2::new(
-                    (self.position.x + glyph.x).round(),
-                    (self.position.y + glyph.y).round(),
-                ),
-                Vec2::new(data.metrics.width as f32, data.metrics.height as f32),
+                position.round() / pipeline.scale,
+                Vec2::new(data.metrics.width as f32, data.metrics.height as f32) / scale,
                 &self.color,
                 None,
                 pipeline.projection,
@@ -184,7 +181,7 @@ impl DrawDispatcher for DrawTextCommand<'_> {
             .fonts
             .entry(self.font.inner.name().unwrap().to_string())
             .or_default();
-        let atlas = set.get_atlas(self.size);
+        let atlas = set.get_atlas((self.size as f32 * pipeline.scale).round() as u32);
 
         let material = atlas.get_or_add_material(gpu);
         renderpass.set_bind_group(0, &material.bind_group, &[]);
@@ -192,6 +189,14 @@ impl DrawDispatcher for DrawTextCommand<'_> {
     }
 }
 
+/// Characters laid out without a glyph to draw.
+pub(crate) fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        ' ' | '\t' | '\n' | '\r' | '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{FEFF}'
+    ) || c.is_control()
+}
+
 #[enum_dispatch]
 pub enum DrawCommand<'frame> {
     Rect(DrawRectCommand),
@@ -216,7 +221,11 @@ pub struct PackedGroup<'frame> {
     inner: Vec<DrawCommand<'frame>>,
 }
 
-impl PackedGroup<'_> {
+impl<'frame> PackedGroup<'frame> {
+    pub(crate) fn iter(&self) -> std::slice::Iter<'_, DrawCommand<'frame>> {
+        self.inner.iter()
+    }
+
     pub fn prepare_frame(
         &mut self,
         pipeline: &mut Renderer,
@@ -261,11 +270,10 @@ impl<'frame> CommandBuffer<'frame> {
     }
     pub fn push(&mut self, command: impl Into<DrawCommand<'frame>>) {
         let command = command.into();
-        let last = self.active.last();
-        if let Some(last) = last {
-            if !last.is_same_type(&command) {
-                self.pack_active_group();
-            }
+        if let Some(last) = self.active.last()
+            && !last.is_same_type(&command)
+        {
+            self.pack_active_group();
         }
         self.active.push(command);
     }
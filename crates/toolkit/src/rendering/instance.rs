@@ -63,10 +63,10 @@ impl InstanceData {
             if let Some(stroke) = stroke {
                 (
                     [
-                        stroke.color[0].clone().into(),
-                        stroke.color[1].clone().into(),
-                        stroke.color[2].clone().into(),
-                        stroke.color[3].clone().into(),
+                        stroke.color[0].into(),
+                        stroke.color[1].into(),
+                        stroke.color[2].into(),
+                        stroke.color[3].into(),
                     ],
                     stroke.width,
                     1,
@@ -6,10 +6,13 @@ pub mod mesh;
 
 mod gpu;
 mod instance;
+mod software;
 mod text;
 mod vertex;
 
 pub use gpu::Gpu;
+pub(crate) use software::pixmap_from_bytes;
+pub use software::SoftwareRenderer;
 
 use crate::error::Error;
 use crate::rendering::bind_group_layout::BindGroupLayoutBuilder;
@@ -19,7 +22,7 @@ use crate::rendering::mesh::QuadMesh;
 use crate::rendering::text::FontAtlasSet;
 use crate::rendering::vertex::Vertex;
 use crate::{include_asset_content, load_asset_str};
-use glam::Mat4;
+use glam::{Mat4, Vec2, Vec3};
 use std::collections::HashMap;
 use wgpu::{
     BlendState, Color, ColorTargetState, ColorWrites, CommandEncoderDescriptor, Face,
@@ -29,6 +32,17 @@ use wgpu::{
     ShaderModuleDescriptor, ShaderSource, StoreOp, Surface, TextureViewDescriptor, VertexState,
 };
 
+/// How the windows are drawn, see [`EventLoop::with_backend`](crate::EventLoop::with_backend).
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Renders with wgpu.
+    #[default]
+    Gpu,
+    /// Rasterizes with tiny-skia into shared memory buffers, which works without GPU drivers,
+    /// e.g. in CI, at the cost of CPU time on every frame.
+    Software,
+}
+
 pub struct Renderer {
     render_pipeline: RenderPipeline,
     mesh: QuadMesh,
@@ -37,6 +51,8 @@ pub struct Renderer {
     fonts: HashMap<String, FontAtlasSet>,
 
     projection: Mat4,
+    /// Buffer pixels per surface coordinate of the frame being rendered.
+    scale: f32,
 }
 
 impl Renderer {
@@ -113,6 +129,7 @@ impl Renderer {
             buffer_pool: InstancingPool::new(gpu),
             fonts: HashMap::default(),
             projection: Mat4::IDENTITY,
+            scale: 1.0,
         })
     }
 
@@ -123,6 +140,7 @@ impl Renderer {
         commands: &mut commands::CommandBuffer,
         window_width: f32,
         window_height: f32,
+        scale: f32,
     ) -> Result<(), Error> {
         let texture = surface.get_current_texture()?;
         let image_view = texture
@@ -158,8 +176,16 @@ impl Renderer {
                 label: Some("Render Encoder"),
             });
 
-        self.projection =
-            Mat4::orthographic_rh_gl(0.0, window_width, window_height, 0.0, -1.0, 1.0);
+        // Commands are in surface coordinates, the target texture is in buffer pixels
+        self.scale = scale;
+        self.projection = Mat4::orthographic_rh_gl(
+            0.0,
+            window_width * scale,
+            window_height * scale,
+            0.0,
+            -1.0,
+            1.0,
+        ) * Mat4::from_scale(Vec3::new(scale, scale, 1.0));
 
         {
             self.buffer_pool.clear();
@@ -179,4 +205,9 @@ impl Renderer {
 
         Ok(())
     }
+
+    /// Rounds `value` to the nearest buffer pixel.
+    fn snap(&self, value: Vec2) -> Vec2 {
+        (value * self.scale).round() / self.scale
+    }
 }
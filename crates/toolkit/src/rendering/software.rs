@@ -0,0 +1,409 @@
+use crate::{
+    error::Error,
+    rendering::commands::{
+        is_invisible, CommandBuffer, DrawCommand, DrawRectCommand, DrawTextCommand,
+        DrawTextureCommand,
+    },
+    types::{Argb8888, Bounds, Color},
+    ContentManager,
+};
+use fontdue::Metrics;
+use glam::Vec2;
+use resvg::tiny_skia::{
+    self, BlendMode, ColorU8, FilterQuality, GradientStop, LinearGradient, Paint, Pattern, Pixmap,
+    PixmapPaint, Point, PremultipliedColorU8, Rect, Shader, SpreadMode, Transform,
+};
+use std::collections::HashMap;
+
+struct Glyph {
+    metrics: Metrics,
+    /// One byte of coverage per pixel.
+    coverage: Vec<u8>,
+}
+
+/// Draws the commands on the CPU, for machines without working GPU drivers.
+///
+/// Follows the shader of [`Renderer`](crate::rendering::Renderer): strokes are drawn inside
+/// the bounds and replace the fill, gradients span the whole bounds.
+#[derive(Default)]
+pub struct SoftwareRenderer {
+    /// Frame being drawn, kept between frames of the same size.
+    pixmap: Option<Pixmap>,
+    /// Glyphs of each font, keyed by the rasterized size in buffer pixels.
+    fonts: HashMap<String, HashMap<(u32, char), Glyph>>,
+
+    /// Buffer pixels per surface coordinate of the frame being rendered.
+    scale: f32,
+}
+
+impl SoftwareRenderer {
+    /// Draws the frame into `buffer`, `Argb8888` rows of `width` by `height` buffer pixels.
+    pub fn render(
+        &mut self,
+        buffer: &mut [u8],
+        commands: &mut CommandBuffer,
+        width: u32,
+        height: u32,
+        scale: f32,
+    ) {
+        let mut pixmap = self
+            .pixmap
+            .take()
+            .filter(|p| p.width() == width && p.height() == height)
+            .unwrap_or_else(|| Pixmap::new(width, height).expect("buffer is at least one pixel"));
+
+        self.scale = scale;
+        pixmap.fill(tiny_skia::Color::WHITE);
+
+        for (content, group) in commands.iter_mut() {
+            for command in group.iter() {
+                match command {
+                    DrawCommand::Rect(rect) => self.draw_rect(&mut pixmap, rect),
+                    DrawCommand::Texture(texture) => {
+                        self.draw_texture(&mut pixmap, content, texture)
+                    }
+                    DrawCommand::Text(text) => self.draw_text(&mut pixmap, text),
+                }
+            }
+        }
+
+        // Both are premultiplied, Argb8888 is little endian
+        for (dst, src) in buffer
+            .chunks_exact_mut(4)
+            .zip(pixmap.data().chunks_exact(4))
+        {
+            dst.copy_from_slice(&[src[2], src[1], src[0], src[3]]);
+        }
+
+        self.pixmap = Some(pixmap);
+    }
+
+    /// Bounds in buffer pixels, `None` when nothing is left to draw.
+    fn to_pixels(&self, bounds: &Bounds) -> Option<Rect> {
+        let position = (bounds.position * self.scale).round();
+        let size = (bounds.size * self.scale).round();
+        Rect::from_xywh(position.x, position.y, size.x, size.y)
+    }
+
+    fn draw_rect(&self, pixmap: &mut Pixmap, command: &DrawRectCommand) {
+        let Some(rect) = self.to_pixels(&command.rect) else {
+            return;
+        };
+
+        let (sides, inner) = split_stroke(rect, command.stroke.width * self.scale);
+        if let Some(inner) = inner {
+            pixmap.fill_rect(
+                inner,
+                &paint(&command.color, rect),
+                Transform::identity(),
+                None,
+            );
+        }
+
+        for (side, color) in sides.into_iter().zip(command.stroke.color) {
+            if let Some(side) = side {
+                pixmap.fill_rect(side, &solid(color), Transform::identity(), None);
+            }
+        }
+    }
+
+    fn draw_texture(
+        &self,
+        pixmap: &mut Pixmap,
+        content: &ContentManager,
+        command: &DrawTextureCommand,
+    ) {
+        let Some(rect) = self.to_pixels(&command.rect) else {
+            return;
+        };
+        let Some(mut layer) = Pixmap::new(rect.width() as u32, rect.height() as u32) else {
+            return;
+        };
+        let bounds = layer_bounds(&layer);
+
+        let texture = content.get_pixmap(&command.texture.handle);
+        let paint = Paint {
+            shader: Pattern::new(
+                texture.as_ref(),
+                SpreadMode::Pad,
+                FilterQuality::Bilinear,
+                1.0,
+                Transform::from_scale(
+                    bounds.width() / texture.width() as f32,
+                    bounds.height() / texture.height() as f32,
+                ),
+            ),
+            ..Paint::default()
+        };
+        layer.fill_rect(bounds, &paint, Transform::identity(), None);
+
+        if command.texture.color != Color::Simple(Argb8888::WHITE) {
+            tint(&mut layer, &command.texture.color);
+        }
+
+        let (sides, _) = split_stroke(bounds, command.stroke.width * self.scale);
+        for (side, color) in sides.into_iter().zip(command.stroke.color) {
+            if let Some(side) = side {
+                let mut paint = solid(color);
+                paint.blend_mode = BlendMode::Source;
+                layer.fill_rect(side, &paint, Transform::identity(), None);
+            }
+        }
+
+        pixmap.draw_pixmap(
+            rect.x() as i32,
+            rect.y() as i32,
+            layer.as_ref(),
+            &PixmapPaint::default(),
+            Transform::identity(),
+            None,
+        );
+    }
+
+    fn draw_text(&mut self, pixmap: &mut Pixmap, command: &DrawTextCommand) {
+        let font = &command.font.inner;
+        let glyphs = self
+            .fonts
+            .entry(font.name().unwrap().to_string())
+            .or_default();
+        // Glyphs are rasterized in buffer pixels, like in the glyph atlases of the GPU
+        let size = (command.size as f32 * self.scale).round() as u32;
+
+        for glyph in command.layout.glyphs() {
+            if is_invisible(glyph.parent) {
+                continue;
+            }
+
+            let data = glyphs.entry((size, glyph.parent)).or_insert_with(|| {
+                let (metrics, coverage) = font.rasterize(glyph.parent, size as f32);
+                Glyph { metrics, coverage }
+            });
+
+            let Some(mut layer) =
+                Pixmap::new(data.metrics.width as u32, data.metrics.height as u32)
+            else {
+                continue;
+            };
+            let bounds = layer_bounds(&layer);
+            layer.fill_rect(
+                bounds,
+                &paint(&command.color, bounds),
+                Transform::identity(),
+                None,
+            );
+
+            for (pixel, coverage) in layer.pixels_mut().iter_mut().zip(&data.coverage) {
+                *pixel = multiply(*pixel, [*coverage; 4]);
+            }
+
+            let position = ((command.position + Vec2::new(glyph.x, glyph.y)) * self.scale).round();
+            pixmap.draw_pixmap(
+                position.x as i32,
+                position.y as i32,
+                layer.as_ref(),
+                &PixmapPaint::default(),
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+}
+
+/// Decodes an image into a premultiplied pixmap, see [`Material::from_bytes`](crate::rendering::material::Material::from_bytes).
+pub(crate) fn pixmap_from_bytes(bytes: &[u8]) -> Result<Pixmap, Error> {
+    let image = image::load_from_memory(bytes)?.to_rgba8();
+    let mut pixmap = Pixmap::new(image.width(), image.height()).ok_or(Error::EmptyImage)?;
+
+    for (pixel, rgba) in pixmap.pixels_mut().iter_mut().zip(image.pixels()) {
+        let [r, g, b, a] = rgba.0;
+        *pixel = ColorU8::from_rgba(r, g, b, a).premultiply();
+    }
+
+    Ok(pixmap)
+}
+
+fn layer_bounds(layer: &Pixmap) -> Rect {
+    Rect::from_xywh(0.0, 0.0, layer.width() as f32, layer.height() as f32).unwrap()
+}
+
+fn skia_color(color: Argb8888) -> tiny_skia::Color {
+    tiny_skia::Color::from_rgba8(color.r, color.g, color.b, color.a)
+}
+
+fn solid(color: Argb8888) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color(skia_color(color));
+    paint
+}
+
+/// Paint of `color` over `rect`.
+fn paint(color: &Color, rect: Rect) -> Paint<'static> {
+    let gradient = match color {
+        Color::Simple(color) => return solid(*color),
+        Color::LinearGradient(gradient) => gradient,
+    };
+
+    // The shader goes from `from` to `to` along the direction in uv coordinates, halfway in
+    // the center, and reaches the ends 0.707 away from it
+    let angle = gradient.degree.to_radians();
+    let direction =
+        Vec2::new(angle.cos() / rect.width(), angle.sin() / rect.height()) / (2.0 * 0.707);
+    let half = direction / direction.length_squared() / 2.0;
+    let center = Vec2::new(
+        rect.x() + rect.width() / 2.0,
+        rect.y() + rect.height() / 2.0,
+    );
+
+    let shader = LinearGradient::new(
+        Point::from_xy(center.x - half.x, center.y - half.y),
+        Point::from_xy(center.x + half.x, center.y + half.y),
+        vec![
+            GradientStop::new(0.0, skia_color(gradient.from)),
+            GradientStop::new(1.0, skia_color(gradient.to)),
+        ],
+        SpreadMode::Pad,
+        Transform::identity(),
+    )
+    .unwrap_or(Shader::SolidColor(skia_color(gradient.from)));
+
+    Paint {
+        shader,
+        ..Paint::default()
+    }
+}
+
+/// Left, right, top and bottom sides of a stroke `width` pixels wide inside `rect`, and what
+/// is left inside of them.
+///
+/// Like in the shader, the left and right sides span the whole height and each side takes
+/// precedence over the next ones.
+fn split_stroke(rect: Rect, width: f32) -> ([Option<Rect>; 4], Option<Rect>) {
+    let width = width.max(0.0);
+    let left = width.min(rect.width());
+    let right = width.min(rect.width() - left);
+    let top = width.min(rect.height());
+    let bottom = width.min(rect.height() - top);
+    let inner_width = rect.width() - left - right;
+
+    let sides = [
+        Rect::from_xywh(rect.x(), rect.y(), left, rect.height()),
+        Rect::from_xywh(rect.right() - right, rect.y(), right, rect.height()),
+        Rect::from_xywh(rect.x() + left, rect.y(), inner_width, top),
+        Rect::from_xywh(rect.x() + left, rect.bottom() - bottom, inner_width, bottom),
+    ];
+
+    let inner = Rect::from_xywh(
+        rect.x() + left,
+        rect.y() + top,
+        inner_width,
+        rect.height() - top - bottom,
+    );
+
+    (sides, inner)
+}
+
+/// Multiplies the pixels of `layer` by `color`, like the texture color in the shader.
+fn tint(layer: &mut Pixmap, color: &Color) {
+    let bounds = layer_bounds(layer);
+    let mut tint = Pixmap::new(layer.width(), layer.height()).unwrap();
+    tint.fill_rect(bounds, &paint(color, bounds), Transform::identity(), None);
+
+    for (pixel, tint) in layer.pixels_mut().iter_mut().zip(tint.pixels()) {
+        *pixel = multiply(
+            *pixel,
+            [tint.red(), tint.green(), tint.blue(), tint.alpha()],
+        );
+    }
+}
+
+/// Product of two premultiplied colors, which is premultiplied as well.
+fn multiply(pixel: PremultipliedColorU8, [r, g, b, a]: [u8; 4]) -> PremultipliedColorU8 {
+    let mul = |x: u8, y: u8| ((u16::from(x) * u16::from(y) + 127) / 255) as u8;
+    PremultipliedColorU8::from_rgba(
+        mul(pixel.red(), r),
+        mul(pixel.green(), g),
+        mul(pixel.blue(), b),
+        mul(pixel.alpha(), a),
+    )
+    .unwrap_or(pixel)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec2;
+
+    use super::SoftwareRenderer;
+    use crate::{
+        commands::{CommandBuffer, DrawRectCommand},
+        types::{Argb8888, Bounds, Stroke},
+        ContentManager,
+    };
+
+    const WIDTH: u32 = 8;
+    const HEIGHT: u32 = 6;
+
+    /// Bytes of the pixel at `x`, `y` in the `Argb8888` buffer, i.e. `[b, g, r, a]`.
+    fn pixel(buffer: &[u8], x: u32, y: u32) -> [u8; 4] {
+        let offset = ((y * WIDTH + x) * 4) as usize;
+        buffer[offset..offset + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn draws_rect_fill_and_stroke_sides() {
+        let content = ContentManager::default();
+        let mut commands = CommandBuffer::new(&content);
+        commands.push(DrawRectCommand::new(
+            Bounds::new(Vec2::new(1.0, 1.0), Vec2::new(5.0, 4.0)),
+            Argb8888::RED,
+            Stroke {
+                color: [
+                    Argb8888::GREEN,
+                    Argb8888::BLUE,
+                    Argb8888::YELLOW,
+                    Argb8888::MAGENTA,
+                ],
+                width: 1.0,
+            },
+        ));
+        commands.pack_active_group();
+
+        let mut buffer = vec![0; (WIDTH * HEIGHT * 4) as usize];
+        SoftwareRenderer::default().render(&mut buffer, &mut commands, WIDTH, HEIGHT, 1.0);
+
+        // Background
+        assert_eq!(pixel(&buffer, 0, 0), [255, 255, 255, 255]);
+        assert_eq!(pixel(&buffer, 7, 5), [255, 255, 255, 255]);
+        // Fill inside the stroke
+        assert_eq!(pixel(&buffer, 2, 2), [0, 0, 255, 255]);
+        assert_eq!(pixel(&buffer, 4, 3), [0, 0, 255, 255]);
+        // Left and right sides span the whole height
+        assert_eq!(pixel(&buffer, 1, 1), [0, 255, 0, 255]);
+        assert_eq!(pixel(&buffer, 1, 4), [0, 255, 0, 255]);
+        assert_eq!(pixel(&buffer, 5, 1), [255, 0, 0, 255]);
+        assert_eq!(pixel(&buffer, 5, 4), [255, 0, 0, 255]);
+        // Top and bottom sides between them
+        assert_eq!(pixel(&buffer, 3, 1), [0, 255, 255, 255]);
+        assert_eq!(pixel(&buffer, 3, 4), [255, 0, 255, 255]);
+    }
+
+    #[test]
+    fn scales_to_buffer_pixels() {
+        let content = ContentManager::default();
+        let mut commands = CommandBuffer::new(&content);
+        commands.push(DrawRectCommand::new(
+            Bounds::new(Vec2::new(1.0, 1.0), Vec2::new(2.0, 1.0)),
+            Argb8888::BLUE,
+            Stroke::NONE,
+        ));
+        commands.pack_active_group();
+
+        let mut buffer = vec![0; (WIDTH * HEIGHT * 4) as usize];
+        SoftwareRenderer::default().render(&mut buffer, &mut commands, WIDTH, HEIGHT, 2.0);
+
+        assert_eq!(pixel(&buffer, 1, 1), [255, 255, 255, 255]);
+        assert_eq!(pixel(&buffer, 2, 2), [255, 0, 0, 255]);
+        assert_eq!(pixel(&buffer, 5, 3), [255, 0, 0, 255]);
+        assert_eq!(pixel(&buffer, 6, 3), [255, 255, 255, 255]);
+        assert_eq!(pixel(&buffer, 5, 4), [255, 255, 255, 255]);
+    }
+}
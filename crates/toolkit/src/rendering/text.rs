@@ -8,6 +8,7 @@ use guillotiere::AtlasAllocator;
 use std::collections::HashMap;
 use wgpu::{FilterMode, TextureFormat};
 
+/// Glyph atlases of one font, keyed by the rasterized size in buffer pixels.
 #[derive(Default)]
 pub struct FontAtlasSet {
     inner: HashMap<u32, FontAtlas>,
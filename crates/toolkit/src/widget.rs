@@ -1,12 +1,26 @@
 use crate::{
     commands::CommandBuffer,
-    types::{Bounds, Color},
+    event::SystemEvent,
+    types::Bounds,
     ContentManager, WindowRoot,
 };
 use bitflags::bitflags;
 use glam::Vec2;
-use std::any::Any;
-use wl_client::ButtonState;
+use std::{
+    any::Any,
+    time::{Duration, Instant},
+};
+use wl_client::{
+    clipboard::{SelectionKind, TEXT_MIME_TYPES},
+    dnd::{DragIcon, DragOffer, DroppedData},
+    foreign_toplevel::ForeignToplevel,
+    keyboard::{KeyEvent, Modifiers},
+    text_input::TextInputEvent,
+    touch::TouchPoint,
+    window::WindowState,
+    workspace::Workspace,
+    ButtonState, CursorShape, ResizeEdge, Scroll,
+};
 
 bitflags! {
     #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
@@ -69,11 +83,40 @@ pub enum DesiredSize {
     Ignore,
 }
 
-#[derive(Default)]
 pub struct FrameContext {
     pub(crate) delta_time: f64,
     pub(crate) position: Vec2,
     pub(crate) buttons: ButtonState,
+    pub(crate) scroll: Scroll,
+    pub(crate) touches: Vec<TouchPoint>,
+    pub(crate) keys: Vec<KeyEvent>,
+    pub(crate) modifiers: Modifiers,
+    pub(crate) focused: bool,
+    pub(crate) text_input: Vec<TextInputEvent>,
+    pub(crate) window_state: WindowState,
+    pub(crate) drag: Option<DragOffer>,
+    pub(crate) drops: Vec<DroppedData>,
+    pub(crate) workspaces: Vec<Workspace>,
+}
+
+impl Default for FrameContext {
+    fn default() -> Self {
+        Self {
+            delta_time: 0.0,
+            position: Vec2::MAX,
+            buttons: ButtonState::default(),
+            scroll: Scroll::default(),
+            touches: vec![],
+            keys: vec![],
+            modifiers: Modifiers::default(),
+            focused: false,
+            text_input: vec![],
+            window_state: WindowState::empty(),
+            drag: None,
+            drops: vec![],
+            workspaces: vec![],
+        }
+    }
 }
 
 impl FrameContext {
@@ -91,6 +134,67 @@ impl FrameContext {
     pub const fn buttons(&self) -> ButtonState {
         self.buttons
     }
+
+    /// Scroll wheel and touchpad movement since the previous frame.
+    #[must_use]
+    pub const fn scroll(&self) -> Scroll {
+        self.scroll
+    }
+
+    /// Touch points on the window changed since the previous frame, plus the ones still held.
+    #[must_use]
+    pub fn touches(&self) -> &[TouchPoint] {
+        &self.touches
+    }
+
+    /// Key presses, releases and repeats received by the window since the previous frame.
+    #[must_use]
+    pub fn keys(&self) -> &[KeyEvent] {
+        &self.keys
+    }
+
+    #[must_use]
+    pub const fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Whether the window has the keyboard focus and receives [keys](FrameContext::keys).
+    #[must_use]
+    pub const fn focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Edits from the input method since the previous frame, for the widget calling
+    /// [`Sender::set_text_input`].
+    #[must_use]
+    pub fn text_input(&self) -> &[TextInputEvent] {
+        &self.text_input
+    }
+
+    /// State of the window last configured by the compositor, e.g. whether it is maximized
+    /// or draws the decorations itself.
+    #[must_use]
+    pub const fn window_state(&self) -> WindowState {
+        self.window_state
+    }
+
+    /// Drag over the window, which the widget under it may accept with [`Sender::accept_drag`].
+    #[must_use]
+    pub const fn drag(&self) -> Option<&DragOffer> {
+        self.drag.as_ref()
+    }
+
+    /// Drags dropped on the window since the previous frame, for the widget under their position.
+    #[must_use]
+    pub fn drops(&self) -> &[DroppedData] {
+        &self.drops
+    }
+
+    /// Workspaces of the compositor, empty without `ext_workspace_manager_v1`.
+    #[must_use]
+    pub fn workspaces(&self) -> &[Workspace] {
+        &self.workspaces
+    }
 }
 
 pub trait Widget<C: Context>: WidgetQuery<C> + Any + Sync + Send + Default {
@@ -111,16 +215,24 @@ pub trait Context: Send + Sync + Default + Sized + 'static {
     type Widget: Widget<Self>;
     type WindowRoot: WindowRoot<Self, Self::Widget>;
     fn execute(&self, content: &mut ContentManager, tree: &mut Tree<Self>);
+
+    /// Maps an event raised by the compositor to a context which is then executed.
+    #[allow(unused_variables)]
+    fn on_event(event: &SystemEvent) -> Option<Self> {
+        None
+    }
 }
 
 pub struct Sender<C: Context> {
     inner: Vec<C>,
+    actions: Vec<WindowAction>,
 }
 
 impl<C: Context> Default for Sender<C> {
     fn default() -> Self {
         Self {
             inner: Vec::with_capacity(32),
+            actions: vec![],
         }
     }
 }
@@ -136,18 +248,294 @@ impl<C: Context> Sender<C> {
     pub fn create_event(&mut self, event: C) {
         self.inner.push(event);
     }
+
+    /// Lets the user drag the window, must be called in the frame receiving the press.
+    ///
+    /// Like the other window requests below, only applies to desktop windows.
+    pub fn start_move(&mut self) {
+        self.actions.push(WindowAction::Move);
+    }
+
+    /// Lets the user resize the window from `edge`, see [`Sender::start_move`].
+    pub fn start_resize(&mut self, edge: ResizeEdge) {
+        self.actions.push(WindowAction::Resize(edge));
+    }
+
+    /// Shows the compositor window menu at `position`, relative to the window.
+    pub fn show_window_menu(&mut self, position: Vec2) {
+        self.actions.push(WindowAction::ShowMenu(position));
+    }
+
+    pub fn set_maximized(&mut self, maximized: bool) {
+        self.actions
+            .push(WindowAction::Toplevel(ToplevelAction::SetMaximized(maximized)));
+    }
+
+    pub fn minimize(&mut self) {
+        self.actions
+            .push(WindowAction::Toplevel(ToplevelAction::Minimize));
+    }
+
+    /// Handled like the close button of a server-side titlebar: contexts receive
+    /// [`SystemEvent::CloseRequested`] first and the window is closed if none handles it.
+    pub fn request_close(&mut self) {
+        self.actions.push(WindowAction::Close);
+    }
+
+    /// Shows `shape` while the pointer is over the window, the default arrow is shown when
+    /// no widget asks for a cursor during a frame.
+    pub fn set_cursor(&mut self, shape: CursorShape) {
+        self.actions.push(WindowAction::SetCursor(shape));
+    }
+
+    /// Accepts the [drag](FrameContext::drag) over the window as `mime_type`, must be called
+    /// every frame while the drag is over the widget or the drag is rejected.
+    pub fn accept_drag(&mut self, mime_type: impl Into<String>) {
+        self.actions.push(WindowAction::AcceptDrag(mime_type.into()));
+    }
+
+    /// Starts dragging `data` offered as each of the `mime_types`, see [`Sender::start_move`].
+    ///
+    /// The outcome is delivered as [`SystemEvent::DragFinished`] or [`SystemEvent::DragCancelled`].
+    pub fn start_drag(&mut self, mime_types: Vec<String>, data: Vec<u8>, icon: Option<DragIcon>) {
+        self.actions
+            .push(WindowAction::StartDrag(mime_types, data, icon));
+    }
+
+    /// Makes the widget the target of the input method, with the text cursor at `cursor`
+    /// relative to the window.
+    ///
+    /// Must be called every frame while the widget has the text focus, the input method is
+    /// turned off during frames where no widget calls it.
+    pub fn set_text_input(&mut self, cursor: Bounds) {
+        self.actions.push(WindowAction::TextInput(cursor));
+    }
+
+    /// Draws the window again at the next frame, for widgets whose looks changed during
+    /// [`Widget::update`]. Animations call it every frame until they end.
+    ///
+    /// Widgets changed through [`Tree::get_mut_element`] call it at their next update, e.g.
+    /// text whose value was set to a different one.
+    pub fn request_redraw(&mut self) {
+        self.actions.push(WindowAction::Redraw);
+    }
+
+    /// Updates the widgets again no later than `deadline` even if no input arrives, e.g. to
+    /// fire a timer. The earliest deadline asked for during a frame wins.
+    pub fn wake_at(&mut self, deadline: Instant) {
+        self.actions.push(WindowAction::WakeAt(deadline));
+    }
+
+    /// Switches to the workspace with this [id](Workspace::id).
+    pub fn activate_workspace(&mut self, id: u64) {
+        self.actions.push(WindowAction::ActivateWorkspace(id));
+    }
+
+    pub(crate) fn take_actions(&mut self) -> Vec<WindowAction> {
+        std::mem::take(&mut self.actions)
+    }
 }
 
 pub trait Callbacks: Send + Sync + Default + 'static {}
 
+pub(crate) enum WindowCommand<C: Context> {
+    Open(C::WindowRoot),
+    Close(String),
+    Toplevel(String, ToplevelAction),
+    UnlockSession,
+}
+
+pub(crate) enum ToplevelAction {
+    SetTitle(String),
+    SetMaximized(bool),
+    Minimize,
+    SetFullscreen(bool),
+    SetMinSize(u32, u32),
+    SetMaxSize(u32, u32),
+}
+
+/// Requests widgets make for the window they belong to.
+pub(crate) enum WindowAction {
+    Move,
+    Resize(ResizeEdge),
+    ShowMenu(Vec2),
+    SetCursor(CursorShape),
+    AcceptDrag(String),
+    StartDrag(Vec<String>, Vec<u8>, Option<DragIcon>),
+    TextInput(Bounds),
+    Redraw,
+    WakeAt(Instant),
+    Toplevel(ToplevelAction),
+    ActivateWorkspace(u64),
+    Close,
+}
+
+pub(crate) enum ClipboardCommand {
+    Set(SelectionKind, Vec<String>, Vec<u8>),
+    Clear(SelectionKind),
+    /// `None` reads the first text MIME type offered.
+    Read(SelectionKind, Option<String>),
+}
+
+/// Requests for the windows of other clients.
+pub(crate) enum ForeignToplevelCommand {
+    Activate(u64),
+    SetMinimized(u64, bool),
+    SetMaximized(u64, bool),
+    Close(u64),
+}
+
+pub(crate) enum IdleCommand {
+    Inhibit(String, bool),
+    Watch(Duration),
+    Unwatch(Duration),
+}
+
 pub struct Tree<'a, C: Context> {
     pub(crate) frontends: &'a mut [C::WindowRoot],
+    pub(crate) commands: &'a mut Vec<WindowCommand<C>>,
+    pub(crate) clipboard: &'a mut Vec<ClipboardCommand>,
+    pub(crate) toplevels: &'a [ForeignToplevel],
+    pub(crate) toplevel_commands: &'a mut Vec<ForeignToplevelCommand>,
+    pub(crate) idle: &'a mut Vec<IdleCommand>,
 }
 
 impl<C> Tree<'_, C>
 where
     C: Context,
 {
+    /// Opens a window for `root` before the next frame, as [`App::add_window`](crate::app::App::add_window)
+    /// does before the event loop starts.
+    ///
+    /// The id of the window request must not be used by another open window.
+    pub fn open_window(&mut self, root: C::WindowRoot) {
+        self.commands.push(WindowCommand::Open(root));
+    }
+
+    /// Closes the window with this id before the next frame.
+    ///
+    /// Windows created for [`TargetMonitor::All`](crate::TargetMonitor::All) are closed on
+    /// every output.
+    pub fn close_window(&mut self, id: impl Into<String>) {
+        self.commands.push(WindowCommand::Close(id.into()));
+    }
+
+    /// Closes the [lock](crate::window::WindowRequest::lock) windows and unlocks the session,
+    /// e.g. once the password is verified.
+    pub fn unlock_session(&mut self) {
+        self.commands.push(WindowCommand::UnlockSession);
+    }
+
+    /// Like the other toplevel requests below, only applies to desktop windows.
+    pub fn set_title(&mut self, id: impl Into<String>, title: impl Into<String>) {
+        self.toplevel(id, ToplevelAction::SetTitle(title.into()));
+    }
+
+    pub fn set_maximized(&mut self, id: impl Into<String>, maximized: bool) {
+        self.toplevel(id, ToplevelAction::SetMaximized(maximized));
+    }
+
+    pub fn minimize(&mut self, id: impl Into<String>) {
+        self.toplevel(id, ToplevelAction::Minimize);
+    }
+
+    pub fn set_fullscreen(&mut self, id: impl Into<String>, fullscreen: bool) {
+        self.toplevel(id, ToplevelAction::SetFullscreen(fullscreen));
+    }
+
+    /// `0` removes the limit for that dimension.
+    pub fn set_min_size(&mut self, id: impl Into<String>, width: u32, height: u32) {
+        self.toplevel(id, ToplevelAction::SetMinSize(width, height));
+    }
+
+    /// `0` removes the limit for that dimension.
+    pub fn set_max_size(&mut self, id: impl Into<String>, width: u32, height: u32) {
+        self.toplevel(id, ToplevelAction::SetMaxSize(width, height));
+    }
+
+    /// Offers `data` as each of the `mime_types` until another client takes the selection.
+    pub fn set_selection(&mut self, kind: SelectionKind, mime_types: Vec<String>, data: Vec<u8>) {
+        self.clipboard
+            .push(ClipboardCommand::Set(kind, mime_types, data));
+    }
+
+    /// Offers `text` as the usual text MIME types.
+    pub fn set_selection_text(&mut self, kind: SelectionKind, text: impl Into<String>) {
+        let mime_types = TEXT_MIME_TYPES.iter().map(ToString::to_string).collect();
+        self.set_selection(kind, mime_types, text.into().into_bytes());
+    }
+
+    pub fn clear_selection(&mut self, kind: SelectionKind) {
+        self.clipboard.push(ClipboardCommand::Clear(kind));
+    }
+
+    /// Contents are delivered as [`SystemEvent::SelectionReceived`], nothing is received
+    /// when the selection can't be read as `mime_type`.
+    pub fn read_selection(&mut self, kind: SelectionKind, mime_type: impl Into<String>) {
+        self.clipboard
+            .push(ClipboardCommand::Read(kind, Some(mime_type.into())));
+    }
+
+    /// Like [`Tree::read_selection`] with the preferred text MIME type the selection offers.
+    pub fn read_selection_text(&mut self, kind: SelectionKind) {
+        self.clipboard.push(ClipboardCommand::Read(kind, None));
+    }
+
+    /// Windows of every client, e.g. for a taskbar. Changes are delivered as
+    /// [`SystemEvent::ToplevelAdded`], [`SystemEvent::ToplevelChanged`] and
+    /// [`SystemEvent::ToplevelRemoved`].
+    #[must_use]
+    pub fn toplevels(&self) -> &[ForeignToplevel] {
+        self.toplevels
+    }
+
+    /// Focuses the toplevel with this [id](ForeignToplevel::id), unminimizing it if needed.
+    ///
+    /// Like the other requests below, ignored when the compositor only lists toplevels
+    /// with `ext_foreign_toplevel_list_v1`.
+    pub fn activate_toplevel(&mut self, id: u64) {
+        self.toplevel_commands
+            .push(ForeignToplevelCommand::Activate(id));
+    }
+
+    pub fn set_toplevel_minimized(&mut self, id: u64, minimized: bool) {
+        self.toplevel_commands
+            .push(ForeignToplevelCommand::SetMinimized(id, minimized));
+    }
+
+    pub fn set_toplevel_maximized(&mut self, id: u64, maximized: bool) {
+        self.toplevel_commands
+            .push(ForeignToplevelCommand::SetMaximized(id, maximized));
+    }
+
+    /// Asks the app owning the toplevel to close it, which it may refuse.
+    pub fn close_toplevel(&mut self, id: u64) {
+        self.toplevel_commands
+            .push(ForeignToplevelCommand::Close(id));
+    }
+
+    /// Keeps the screen from blanking or locking while the window with this id is visible,
+    /// e.g. during a presentation.
+    pub fn set_idle_inhibited(&mut self, id: impl Into<String>, inhibited: bool) {
+        self.idle.push(IdleCommand::Inhibit(id.into(), inhibited));
+    }
+
+    /// Raises [`SystemEvent::Idled`] once the user gave no input for `timeout`, and
+    /// [`SystemEvent::Resumed`] when they come back. Several timeouts can be watched at once,
+    /// e.g. one to dim the screen and a longer one to pause timers.
+    pub fn watch_idle(&mut self, timeout: Duration) {
+        self.idle.push(IdleCommand::Watch(timeout));
+    }
+
+    pub fn unwatch_idle(&mut self, timeout: Duration) {
+        self.idle.push(IdleCommand::Unwatch(timeout));
+    }
+
+    fn toplevel(&mut self, id: impl Into<String>, action: ToplevelAction) {
+        self.commands
+            .push(WindowCommand::Toplevel(id.into(), action));
+    }
+
     #[must_use]
     pub fn get_element<QW: Widget<C>>(&self, id: &str) -> Option<&QW> {
         for frontend in self.frontends.iter() {
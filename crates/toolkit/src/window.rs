@@ -1,4 +1,7 @@
-use crate::rendering::Renderer;
+use crate::commands::CommandBuffer;
+use crate::rendering::{Gpu, Renderer, SoftwareRenderer};
+use crate::types::Bounds;
+use crate::Error;
 use std::ffi::c_void;
 use std::ptr::NonNull;
 use wgpu::rwh::{
@@ -6,9 +9,10 @@ use wgpu::rwh::{
     RawWindowHandle, WaylandDisplayHandle, WaylandWindowHandle, WindowHandle,
 };
 use wgpu::{Surface, SurfaceConfiguration};
-use wl_client::window::{DesktopOptions, SpecialOptions, WindowLayer};
+use wl_client::window::{DesktopOptions, PopupOptions, SpecialOptions, WindowId, WindowLayer};
 use wl_client::WindowBackend;
 
+#[derive(Clone)]
 pub struct WindowRequest {
     pub(crate) id: String,
     pub(crate) layer: WindowLayer,
@@ -26,6 +30,7 @@ impl WindowRequest {
         }
     }
 
+    /// Layer windows accept `0` to fill the space between their anchors, see [`SpecialOptions`].
     #[must_use]
     pub fn with_size(mut self, width: u32, height: u32) -> Self {
         self.width = width;
@@ -62,29 +67,151 @@ impl WindowRequest {
         self.layer = WindowLayer::Background(options);
         self
     }
+
+    /// Popup placed below `anchor`, given in the coordinates of the `parent` window.
+    ///
+    /// With a `parent` shown on every monitor, it opens on the instance the pointer or
+    /// keyboard is in.
+    #[must_use]
+    pub fn popup(self, parent: impl Into<String>, anchor: Bounds) -> Self {
+        self.popup_with(PopupOptions::new(parent, anchor.position, anchor.size))
+    }
+
+    #[must_use]
+    pub fn popup_with(mut self, options: PopupOptions) -> Self {
+        self.layer = WindowLayer::Popup(options);
+        self
+    }
+
+    /// Locks the session and covers every output with an instance of the window until
+    /// [`Tree::unlock_session`](crate::widget::Tree::unlock_session).
+    ///
+    /// The compositor sets the size to the one of each output. Without support for
+    /// `ext_session_lock_manager_v1` the window is never shown and
+    /// [`SystemEvent::SessionLockFinished`](crate::event::SystemEvent::SessionLockFinished) is sent.
+    #[must_use]
+    pub fn lock(mut self) -> Self {
+        self.layer = WindowLayer::Lock;
+        self
+    }
+}
+
+/// Draws the frames of a window with the [`Backend`](crate::Backend) of the event loop.
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum WindowRenderer {
+    Gpu {
+        surface: Surface<'static>,
+        configuration: SurfaceConfiguration,
+        renderer: Renderer,
+    },
+    Software(SoftwareRenderer),
+}
+
+impl WindowRenderer {
+    /// Follows the size and scale of the backend, `gpu` is `None` for the software renderer.
+    pub(crate) fn resize(
+        &mut self,
+        gpu: Option<&Gpu>,
+        backend: &mut wl_client::window::Window,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Gpu {
+                surface,
+                configuration,
+                ..
+            } => {
+                let (width, height) = backend.physical_size();
+                configuration.width = width.try_into().map_err(|_| Error::NegativeWidth(width))?;
+                configuration.height = height
+                    .try_into()
+                    .map_err(|_| Error::NegativeHeight(height))?;
+
+                gpu.expect("gpu windows are created with a gpu")
+                    .confugure_surface(surface, configuration);
+            }
+            Self::Software(_) => backend.resize_buffer_if_needed(),
+        }
+
+        Ok(())
+    }
+
+    /// Draws `commands` and commits the frame to the compositor.
+    pub(crate) fn render(
+        &mut self,
+        gpu: Option<&Gpu>,
+        backend: &mut wl_client::window::Window,
+        commands: &mut CommandBuffer,
+        scale: f32,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Gpu {
+                surface, renderer, ..
+            } => {
+                renderer.render(
+                    gpu.expect("gpu windows are created with a gpu"),
+                    surface,
+                    commands,
+                    backend.width as f32,
+                    backend.height as f32,
+                    scale,
+                )?;
+                backend.commit();
+            }
+            Self::Software(renderer) => {
+                let (width, height) = backend.physical_size();
+                renderer.render(
+                    backend.buffer_mut(),
+                    commands,
+                    width as u32,
+                    height as u32,
+                    scale,
+                );
+                backend.draw();
+            }
+        }
+
+        Ok(())
+    }
 }
 
 pub struct Window {
     pub(crate) backend: WindowBackend,
-    pub(crate) surface: Surface<'static>,
-    pub(crate) configuration: SurfaceConfiguration,
-    pub(crate) renderer: Renderer,
+    pub(crate) renderer: WindowRenderer,
+    /// Name of the output the window was created on, `None` when the compositor chose it.
+    pub(crate) output: Option<String>,
 }
 
 impl Window {
     pub(crate) const fn new(
         backend: WindowBackend,
-        surface: Surface<'static>,
-        configuration: SurfaceConfiguration,
-        renderer: Renderer,
+        renderer: WindowRenderer,
+        output: Option<String>,
     ) -> Self {
         Self {
             backend,
-            surface,
-            configuration,
             renderer,
+            output,
         }
     }
+
+    pub(crate) fn id(&self) -> WindowId {
+        self.backend.lock().unwrap().id.clone()
+    }
+
+    /// Whether the window covers an output while the session is locked.
+    pub(crate) fn is_lock(&self) -> bool {
+        matches!(self.backend.lock().unwrap().layer, WindowLayer::Lock)
+    }
+
+    /// Id of the window this popup is attached to.
+    pub(crate) fn parent(&self) -> Option<String> {
+        self.backend
+            .lock()
+            .unwrap()
+            .layer
+            .parent()
+            .map(ToString::to_string)
+    }
 }
 
 pub struct WindowPointer {
@@ -3,12 +3,13 @@ use toolkit::{
     glam::Vec2,
     types::{styling::BackgroundStyle, Argb8888, Bounds, Color, Stroke},
     widget::{
-        Anchor, Context, DefaultID, DesiredSize, NoID, Sender, Spacing, StaticID, Widget, WidgetID,
+        Anchor, Context, DefaultID, DesiredSize, FrameContext, NoID, Sender, Spacing, StaticID,
+        Widget, WidgetID,
     },
-    WidgetQuery,
+    CursorShape, TouchPhase, WidgetQuery,
 };
 
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 enum ButtonFsm {
     #[default]
     Normal,
@@ -64,9 +65,12 @@ where
     pub alignment: Alignment,
     pub padding: Spacing,
     pub anchor: Anchor,
+    /// Treat a single finger tap on the button like a left click.
+    pub tap_as_click: bool,
 
     rect: Bounds,
     state: ButtonFsm,
+    clicked: bool,
 
     callbacks: CB,
     id: ID::IdType,
@@ -166,8 +170,10 @@ where
             callbacks: CB::default(),
             rect: Bounds::ZERO,
             state: ButtonFsm::Normal,
+            clicked: false,
             alignment: Alignment::Center,
             anchor: Anchor::Left,
+            tap_as_click: false,
             padding: Spacing {
                 left: 2.0,
                 right: 2.0,
@@ -184,6 +190,34 @@ where
     pub fn content(&self) -> &W {
         &self.content
     }
+
+    /// Whether the button was pressed and released over it during the last update, for
+    /// widgets made of several buttons.
+    #[must_use]
+    pub const fn clicked(&self) -> bool {
+        self.clicked
+    }
+
+    /// Returns the pointing position and whether it is pressed, or `None` when a tap was cancelled.
+    fn input(&self, ctx: &FrameContext) -> Option<(Vec2, bool)> {
+        if self.tap_as_click
+            && let [touch] = ctx.touches()
+        {
+            return match touch.phase() {
+                TouchPhase::Started | TouchPhase::Moved => Some((touch.position(), true)),
+                TouchPhase::Ended => Some((touch.position(), false)),
+                TouchPhase::Cancelled => None,
+            };
+        }
+
+        Some((ctx.position(), ctx.buttons().left()))
+    }
+
+    /// A tap has no hover before it, it presses the button as soon as it starts.
+    fn tap_started(&self, ctx: &FrameContext) -> bool {
+        self.tap_as_click
+            && matches!(ctx.touches(), [touch] if touch.phase() == TouchPhase::Started)
+    }
 }
 
 impl<C, W, CB, ID> Widget<C> for Button<C, W, CB, ID>
@@ -279,14 +313,31 @@ where
         self.content.layout(content_rect);
     }
 
-    fn update(&mut self, ctx: &toolkit::widget::FrameContext, sender: &mut Sender<C>) {
-        let is_inside = self.rect.contains(ctx.position());
-        let is_pressed = ctx.buttons().left();
+    fn update(&mut self, ctx: &FrameContext, sender: &mut Sender<C>) {
+        self.clicked = false;
+        let Some((position, is_pressed)) = self.input(ctx) else {
+            if !matches!(self.state, ButtonFsm::Normal) {
+                self.state = ButtonFsm::Normal;
+                self.callbacks.on_exit(sender);
+                sender.request_redraw();
+            }
+
+            self.content.update(ctx, sender);
+            return;
+        };
+
+        let is_inside = self.rect.contains(position);
+        let previous = self.state;
         match self.state {
             ButtonFsm::Normal => {
                 if is_inside {
                     self.state = ButtonFsm::Hovered;
                     self.callbacks.on_enter(sender);
+
+                    if is_pressed && self.tap_started(ctx) {
+                        self.state = ButtonFsm::Pressed;
+                        self.callbacks.on_press(sender);
+                    }
                 }
             }
 
@@ -303,6 +354,7 @@ where
             ButtonFsm::Pressed => {
                 if !is_pressed {
                     self.state = ButtonFsm::Hovered;
+                    self.clicked = true;
                     self.callbacks.on_clicked(sender);
                 } else if !is_inside {
                     self.state = ButtonFsm::PressedOutside;
@@ -318,6 +370,15 @@ where
             }
         }
 
+        // Styled by state
+        if self.state != previous {
+            sender.request_redraw();
+        }
+
+        if matches!(self.state, ButtonFsm::Hovered | ButtonFsm::Pressed) {
+            sender.set_cursor(CursorShape::Pointer);
+        }
+
         self.content.update(ctx, sender);
     }
 }
@@ -0,0 +1,432 @@
+use crate::text::Text;
+use toolkit::{
+    commands::{CommandBuffer, DrawRectCommand},
+    glam::Vec2,
+    types::{Argb8888, Bounds, Stroke},
+    widget::{
+        Anchor, Context, DefaultID, DesiredSize, FrameContext, NoID, Sender, StaticID, Widget,
+        WidgetID,
+    },
+    CursorShape, FontHandle, ResizeEdge, WidgetQuery, WindowState,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TitlebarButton {
+    Minimize,
+    Maximize,
+    Close,
+}
+
+impl TitlebarButton {
+    /// In the order they are laid out from the right edge of the titlebar.
+    const ALL: [Self; 3] = [Self::Close, Self::Maximize, Self::Minimize];
+}
+
+/// Client-side titlebar and resize frame around `content`.
+///
+/// Desktop windows have no decorations when the compositor doesn't draw them or
+/// [`DesktopOptions::decorations`](toolkit::DesktopOptions::decorations) is `false`.
+/// The frame lets those windows be moved, resized, minimized, maximized and closed,
+/// and shows the compositor window menu on a right click on the titlebar. It steps aside
+/// for server-side decorations and fullscreen windows, leaving the whole window to `content`.
+#[derive(WidgetQuery)]
+pub struct Decorations<C, W, ID = DefaultID>
+where
+    C: Context,
+    W: Widget<C>,
+    ID: WidgetID,
+{
+    pub anchor: Anchor,
+    pub titlebar_height: f32,
+    /// Width of the resize handles around the window, dropped while maximized or tiled.
+    pub border: f32,
+    pub background: Argb8888,
+    pub button_hover: Argb8888,
+    pub close_hover: Argb8888,
+    pub glyph_color: Argb8888,
+
+    title: Text<C, NoID>,
+    bounds: Bounds,
+    titlebar: Bounds,
+    hovered: Option<TitlebarButton>,
+    /// Pressed and not released yet, clicked if the pointer is still over it on release.
+    pressed: Option<TitlebarButton>,
+    previous_left: bool,
+    previous_right: bool,
+    state: WindowState,
+
+    id: ID::IdType,
+    #[content]
+    content: W,
+
+    _phantom: std::marker::PhantomData<C>,
+}
+
+impl<C, W> Decorations<C, W, NoID>
+where
+    C: Context,
+    W: Widget<C>,
+{
+    #[must_use]
+    pub fn new() -> Self {
+        Self::new_with_id(())
+    }
+}
+
+impl<C, W> Decorations<C, W, StaticID>
+where
+    C: Context,
+    W: Widget<C>,
+{
+    #[must_use]
+    pub fn new_static(id: &'static str) -> Self {
+        Self::new_with_id(id)
+    }
+}
+
+impl<C, W> Decorations<C, W, DefaultID>
+where
+    C: Context,
+    W: Widget<C>,
+{
+    #[must_use]
+    pub fn new_default() -> Self {
+        Self::new_with_id(None)
+    }
+
+    #[must_use]
+    pub fn new_id(id: impl Into<String>) -> Self {
+        Self::new_with_id(Some(id.into()))
+    }
+}
+
+impl<C, W, ID> Default for Decorations<C, W, ID>
+where
+    C: Context,
+    W: Widget<C>,
+    ID: WidgetID,
+{
+    fn default() -> Self {
+        Self::new_with_id(ID::IdType::default())
+    }
+}
+
+impl<C, W, ID> Decorations<C, W, ID>
+where
+    C: Context,
+    W: Widget<C>,
+    ID: WidgetID,
+{
+    fn new_with_id(id: ID::IdType) -> Self {
+        let mut title = Text::new();
+        title.size = 14;
+        title.anchor = Anchor::VerticalCenter;
+
+        Self {
+            anchor: Anchor::Left,
+            titlebar_height: 30.0,
+            border: 6.0,
+            background: Argb8888::new(48, 48, 48, 255),
+            button_hover: Argb8888::new(80, 80, 80, 255),
+            close_hover: Argb8888::new(196, 43, 28, 255),
+            glyph_color: Argb8888::WHITE,
+            title,
+            bounds: Bounds::ZERO,
+            titlebar: Bounds::ZERO,
+            hovered: None,
+            pressed: None,
+            previous_left: false,
+            previous_right: false,
+            state: WindowState::empty(),
+            id,
+            content: W::default(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Only shown by the titlebar, the compositor title is set with
+    /// [`Tree::set_title`](toolkit::widget::Tree::set_title).
+    pub fn set_title(&mut self, title: &str) {
+        self.title.set_text(title);
+    }
+
+    pub fn set_font(&mut self, font: FontHandle) {
+        self.title.set_font(font);
+    }
+
+    #[must_use]
+    pub const fn content_mut(&mut self) -> &mut W {
+        &mut self.content
+    }
+
+    #[must_use]
+    pub const fn content(&self) -> &W {
+        &self.content
+    }
+
+    fn visible(&self) -> bool {
+        !self
+            .state
+            .intersects(WindowState::ServerDecorations | WindowState::Fullscreen)
+    }
+
+    fn border(&self) -> f32 {
+        if self
+            .state
+            .intersects(WindowState::Maximized | WindowState::Tiled)
+        {
+            0.0
+        } else {
+            self.border
+        }
+    }
+
+    fn button_bounds(&self, button: TitlebarButton) -> Bounds {
+        let index = TitlebarButton::ALL
+            .iter()
+            .position(|b| *b == button)
+            .unwrap_or_default();
+
+        let size = self.titlebar.size.y;
+        Bounds::new(
+            Vec2::new(
+                self.titlebar.position.x + self.titlebar.size.x - size * (index + 1) as f32,
+                self.titlebar.position.y,
+            ),
+            Vec2::splat(size),
+        )
+    }
+
+    fn button_at(&self, position: Vec2) -> Option<TitlebarButton> {
+        TitlebarButton::ALL
+            .into_iter()
+            .find(|button| self.button_bounds(*button).contains(position))
+    }
+
+    fn edge_at(&self, position: Vec2) -> Option<ResizeEdge> {
+        let border = self.border();
+        if border <= 0.0 || !self.bounds.contains(position) {
+            return None;
+        }
+
+        let start = position - self.bounds.position;
+        let end = self.bounds.size - start;
+        let left = start.x < border;
+        let right = end.x <= border;
+        let top = start.y < border;
+        let bottom = end.y <= border;
+
+        match (left, right, top, bottom) {
+            (true, _, true, _) => Some(ResizeEdge::TopLeft),
+            (_, true, true, _) => Some(ResizeEdge::TopRight),
+            (true, _, _, true) => Some(ResizeEdge::BottomLeft),
+            (_, true, _, true) => Some(ResizeEdge::BottomRight),
+            (true, ..) => Some(ResizeEdge::Left),
+            (_, true, ..) => Some(ResizeEdge::Right),
+            (_, _, true, _) => Some(ResizeEdge::Top),
+            (.., true) => Some(ResizeEdge::Bottom),
+            _ => None,
+        }
+    }
+
+    fn click(&self, button: TitlebarButton, sender: &mut Sender<C>) {
+        match button {
+            TitlebarButton::Minimize => sender.minimize(),
+            TitlebarButton::Maximize => {
+                sender.set_maximized(!self.state.contains(WindowState::Maximized));
+            }
+            TitlebarButton::Close => sender.request_close(),
+        }
+    }
+
+    fn draw_glyph<'frame>(&self, button: TitlebarButton, out: &mut CommandBuffer<'frame>) {
+        let bounds = self.button_bounds(button);
+        let center = bounds.position + bounds.size / 2.0;
+        let size = (bounds.size.y / 3.0).round();
+
+        match button {
+            TitlebarButton::Minimize => out.push(DrawRectCommand::new(
+                Bounds::new(
+                    Vec2::new(center.x - size / 2.0, center.y + size / 2.0 - 1.0),
+                    Vec2::new(size, 1.0),
+                ),
+                self.glyph_color,
+                Stroke::NONE,
+            )),
+            TitlebarButton::Maximize => {
+                // Smaller square while maximized, like the restore glyph of most themes
+                let size = if self.state.contains(WindowState::Maximized) {
+                    size - 2.0
+                } else {
+                    size
+                };
+
+                out.push(DrawRectCommand::new(
+                    Bounds::new(center - size / 2.0, Vec2::splat(size)),
+                    Argb8888::TRANSPARENT,
+                    Stroke {
+                        color: [self.glyph_color; 4],
+                        width: 1.0,
+                    },
+                ));
+            }
+            TitlebarButton::Close => out.push(DrawRectCommand::new(
+                Bounds::new(center - size / 4.0, Vec2::splat(size / 2.0)),
+                self.glyph_color,
+                Stroke::NONE,
+            )),
+        }
+    }
+}
+
+impl<C, W, ID> Widget<C> for Decorations<C, W, ID>
+where
+    C: Context,
+    W: Widget<C>,
+    ID: WidgetID,
+{
+    fn anchor(&self) -> Anchor {
+        self.anchor
+    }
+
+    fn desired_size(&self) -> DesiredSize {
+        DesiredSize::Fill
+    }
+
+    fn draw<'frame>(&'frame self, out: &mut CommandBuffer<'frame>) {
+        if !self.visible() {
+            self.content.draw(out);
+            return;
+        }
+
+        out.push(DrawRectCommand::new(
+            self.bounds.clone(),
+            self.background,
+            Stroke::NONE,
+        ));
+
+        if let Some(button) = self.hovered {
+            let color = match button {
+                TitlebarButton::Close => self.close_hover,
+                _ => self.button_hover,
+            };
+
+            out.push(DrawRectCommand::new(
+                self.button_bounds(button),
+                color,
+                Stroke::NONE,
+            ));
+        }
+
+        for button in TitlebarButton::ALL {
+            self.draw_glyph(button, out);
+        }
+
+        self.title.draw(out);
+        self.content.draw(out);
+    }
+
+    fn layout(&mut self, bounds: Bounds) {
+        self.bounds = bounds.clone();
+        if !self.visible() {
+            self.titlebar = Bounds::ZERO;
+            self.content.layout(bounds);
+            return;
+        }
+
+        let border = self.border();
+        let frame = Bounds::new(
+            bounds.position + border,
+            (bounds.size - border * 2.0).max(Vec2::ZERO),
+        );
+
+        let height = self.titlebar_height.min(frame.size.y);
+        self.titlebar = Bounds::new(frame.position, Vec2::new(frame.size.x, height));
+
+        let buttons = height * TitlebarButton::ALL.len() as f32;
+        let title_size = match self.title.desired_size() {
+            DesiredSize::Exact(size) => size,
+            _ => Vec2::ZERO,
+        };
+
+        self.title.layout(Bounds::new(
+            Vec2::new(
+                self.titlebar.position.x + height / 3.0,
+                self.titlebar.position.y + (height - title_size.y) / 2.0,
+            ),
+            Vec2::new((frame.size.x - buttons).max(0.0), title_size.y),
+        ));
+
+        self.content.layout(Bounds::new(
+            frame.position + Vec2::new(0.0, height),
+            Vec2::new(frame.size.x, frame.size.y - height),
+        ));
+    }
+
+    fn update(&mut self, ctx: &FrameContext, sender: &mut Sender<C>) {
+        if self.state != ctx.window_state() {
+            self.state = ctx.window_state();
+            sender.request_redraw();
+        }
+
+        let position = ctx.position();
+        let buttons = ctx.buttons();
+        let left_pressed = buttons.left() && !self.previous_left;
+        let right_pressed = buttons.right() && !self.previous_right;
+        self.previous_left = buttons.left();
+        self.previous_right = buttons.right();
+
+        if !self.visible() {
+            self.hovered = None;
+            self.pressed = None;
+            self.content.update(ctx, sender);
+            return;
+        }
+
+        let hovered = self.button_at(position);
+        if self.hovered != hovered {
+            self.hovered = hovered;
+            sender.request_redraw();
+        }
+
+        let edge = self.edge_at(position);
+        if let Some(edge) = edge {
+            sender.set_cursor(edge_cursor(edge));
+        }
+
+        if left_pressed {
+            if let Some(edge) = edge {
+                sender.start_resize(edge);
+            } else if self.hovered.is_some() {
+                self.pressed = self.hovered;
+            } else if self.titlebar.contains(position) {
+                sender.start_move();
+            }
+        } else if !buttons.left()
+            && let Some(button) = self.pressed.take()
+            && self.hovered == Some(button)
+        {
+            self.click(button, sender);
+        }
+
+        if right_pressed && self.hovered.is_none() && self.titlebar.contains(position) {
+            sender.show_window_menu(position);
+        }
+
+        self.content.update(ctx, sender);
+    }
+}
+
+fn edge_cursor(edge: ResizeEdge) -> CursorShape {
+    match edge {
+        ResizeEdge::Top => CursorShape::NResize,
+        ResizeEdge::Bottom => CursorShape::SResize,
+        ResizeEdge::Left => CursorShape::WResize,
+        ResizeEdge::Right => CursorShape::EResize,
+        ResizeEdge::TopLeft => CursorShape::NwResize,
+        ResizeEdge::TopRight => CursorShape::NeResize,
+        ResizeEdge::BottomLeft => CursorShape::SwResize,
+        ResizeEdge::BottomRight => CursorShape::SeResize,
+        _ => CursorShape::Default,
+    }
+}
@@ -1,11 +1,13 @@
 #![allow(clippy::cast_precision_loss)]
 
 pub mod button;
+pub mod decorations;
 pub mod image;
 pub mod rectangle;
 pub mod row;
 pub mod text;
 pub mod timer;
+pub mod workspaces;
 
 #[macro_export]
 macro_rules! impl_proxy_widget {
@@ -312,7 +312,7 @@ where
             }
             let child_size =
                 Self::get_child_size(&data, &inner_bounds, desired_size, child.anchor());
-            let mut child_position =
+            let child_position =
                 Self::get_child_position(child, child_size, self.spacing, &mut data, &inner_bounds);
             //child_position.x += inner_bounds.position.x;
             let child_rect = Bounds::new(child_position, child_size);
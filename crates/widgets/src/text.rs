@@ -24,6 +24,8 @@ where
     value: String,
     layout: Layout,
     bounds: Bounds,
+    /// Changed since the last update, which then asks for a redraw.
+    dirty: bool,
 
     id: ID::IdType,
 
@@ -90,6 +92,7 @@ where
             layout: Layout::new(CoordinateSystem::PositiveYDown),
             anchor: Anchor::Left,
             bounds: Bounds::ZERO,
+            dirty: false,
             margin: Spacing::ZERO,
             _phantom: std::marker::PhantomData,
         };
@@ -103,7 +106,13 @@ where
         self.refresh_layout();
     }
 
+    /// Redraws the window only when `value` differs from the current text.
     pub fn set_text(&mut self, value: &str) {
+        if self.value == value {
+            return;
+        }
+
+        self.dirty = true;
         self.value.clear();
         self.value.insert_str(0, value);
         self.refresh_layout();
@@ -164,7 +173,7 @@ where
     fn draw<'frame>(&'frame self, out: &mut CommandBuffer<'frame>) {
         out.push(DrawCommand::Text(DrawTextCommand::new(
             self.size,
-            self.color.clone(),
+            self.color,
             self.bounds.position + Vec2::new(self.margin.left, self.margin.top),
             &self.font,
             &self.layout,
@@ -182,5 +191,9 @@ where
         self.refresh_layout();
     }
 
-    fn update(&mut self, _: &toolkit::widget::FrameContext, _: &mut Sender<C>) {}
+    fn update(&mut self, _: &toolkit::widget::FrameContext, sender: &mut Sender<C>) {
+        if std::mem::take(&mut self.dirty) {
+            sender.request_redraw();
+        }
+    }
 }
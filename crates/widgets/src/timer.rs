@@ -1,3 +1,4 @@
+use std::time::{Duration, Instant};
 use toolkit::{
     commands::CommandBuffer,
     types::Bounds,
@@ -25,11 +26,16 @@ where
     CB: TimerCallback<C>,
     ID: WidgetID,
 {
+    /// Seconds between triggers.
     pub interval: f64,
     pub running: bool,
     pub repeat: bool,
+    /// Triggers on the first update after the timer starts, otherwise waits a whole
+    /// `interval` first. On by default.
+    pub fire_immediately: bool,
 
-    elapsed_time: f64,
+    /// Next trigger, `interval` after the timer last triggered. `None` while stopped.
+    deadline: Option<Instant>,
     id: ID::IdType,
     callbacks: CB,
     _phantom: std::marker::PhantomData<C>,
@@ -95,7 +101,8 @@ where
             interval: 0.0,
             running: false,
             repeat: false,
-            elapsed_time: f64::MAX,
+            fire_immediately: true,
+            deadline: None,
             id,
             callbacks: CB::default(),
             _phantom: std::marker::PhantomData,
@@ -117,21 +124,35 @@ where
 
     fn layout(&mut self, _: Bounds) {}
 
-    fn update(&mut self, ctx: &FrameContext, sender: &mut Sender<C>) {
+    fn update(&mut self, _: &FrameContext, sender: &mut Sender<C>) {
         if !self.running {
+            // Starts over once restarted
+            self.deadline = None;
             return;
         }
 
-        if self.elapsed_time < self.interval {
-            self.elapsed_time += ctx.delta_time();
+        let now = Instant::now();
+        let interval = Duration::try_from_secs_f64(self.interval).unwrap_or_default();
+        let deadline = *self.deadline.get_or_insert(if self.fire_immediately {
+            now
+        } else {
+            now + interval
+        });
+        if now < deadline {
+            // Wakes the event loop on time when nothing else draws a frame
+            sender.wake_at(deadline);
             return;
         }
 
-        self.elapsed_time = 0.0;
         self.callbacks.on_triggered(sender);
 
-        if !self.repeat {
+        if self.repeat {
+            let next = now + interval;
+            self.deadline = Some(next);
+            sender.wake_at(next);
+        } else {
             self.running = false;
+            self.deadline = None;
         }
     }
 
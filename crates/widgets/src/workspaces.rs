@@ -0,0 +1,243 @@
+use crate::{
+    button::{Button, ButtonMock, ButtonStyle},
+    text::Text,
+};
+use toolkit::{
+    commands::CommandBuffer,
+    glam::Vec2,
+    types::{styling::BackgroundStyle, Argb8888, Bounds, Color, Stroke},
+    widget::{
+        Anchor, Context, DefaultID, DesiredSize, FrameContext, NoID, Sender, StaticID, Widget,
+        WidgetID,
+    },
+    WidgetQuery, Workspace, WorkspaceState,
+};
+
+type WorkspaceButton<C> = Button<C, Text<C, NoID>, ButtonMock, NoID>;
+
+/// One button per workspace of the compositor, switching to it on click.
+///
+/// Scrolling over the row switches to the previous or next workspace. Hidden workspaces are
+/// left out, and without `ext_workspace_manager_v1` the row stays empty.
+#[derive(WidgetQuery)]
+pub struct Workspaces<C, ID = DefaultID>
+where
+    C: Context,
+    ID: WidgetID,
+{
+    pub anchor: Anchor,
+    /// Only shows the workspaces on the output with this name, all of them when `None`.
+    pub output: Option<String>,
+    pub button_size: Vec2,
+    pub spacing: f32,
+    pub text_size: u32,
+    pub text_color: Argb8888,
+    pub normal: ButtonStyle,
+    pub active: ButtonStyle,
+    pub urgent: ButtonStyle,
+
+    workspaces: Vec<Workspace>,
+    buttons: Vec<WorkspaceButton<C>>,
+    bounds: Bounds,
+
+    id: ID::IdType,
+
+    _phantom: std::marker::PhantomData<C>,
+}
+
+impl<C> Workspaces<C, NoID>
+where
+    C: Context,
+{
+    #[must_use]
+    pub fn new() -> Self {
+        Self::new_with_id(())
+    }
+}
+
+impl<C> Workspaces<C, StaticID>
+where
+    C: Context,
+{
+    #[must_use]
+    pub fn new_static(id: &'static str) -> Self {
+        Self::new_with_id(id)
+    }
+}
+
+impl<C> Workspaces<C, DefaultID>
+where
+    C: Context,
+{
+    #[must_use]
+    pub fn new_default() -> Self {
+        Self::new_with_id(None)
+    }
+
+    #[must_use]
+    pub fn new_id(id: impl Into<String>) -> Self {
+        Self::new_with_id(Some(id.into()))
+    }
+}
+
+impl<C, ID> Default for Workspaces<C, ID>
+where
+    C: Context,
+    ID: WidgetID,
+{
+    fn default() -> Self {
+        Self::new_with_id(ID::IdType::default())
+    }
+}
+
+impl<C, ID> Workspaces<C, ID>
+where
+    C: Context,
+    ID: WidgetID,
+{
+    fn new_with_id(id: ID::IdType) -> Self {
+        let style = |background: Argb8888, stroke: Argb8888| ButtonStyle {
+            background: BackgroundStyle::Color(Color::Simple(background)),
+            stroke: Stroke {
+                color: [stroke; 4],
+                width: 1.0,
+            },
+        };
+
+        Self {
+            anchor: Anchor::Left,
+            output: None,
+            button_size: Vec2::new(30.0, 30.0),
+            spacing: 2.0,
+            text_size: 14,
+            text_color: Argb8888::WHITE,
+            normal: style(Argb8888::new(48, 48, 48, 255), Argb8888::DARK_GRAY),
+            active: style(Argb8888::new(80, 80, 80, 255), Argb8888::LIGHT_GRAY),
+            urgent: style(Argb8888::new(196, 43, 28, 255), Argb8888::DARK_GRAY),
+            workspaces: vec![],
+            buttons: vec![],
+            bounds: Bounds::ZERO,
+            id,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Rebuilds the buttons when the shown workspaces changed, keeping the ones of the
+    /// workspaces still there so that a button held down stays pressed.
+    fn sync(&mut self, ctx: &FrameContext, sender: &mut Sender<C>) {
+        let shown: Vec<_> = ctx
+            .workspaces()
+            .iter()
+            .filter(|w| !w.state().contains(WorkspaceState::Hidden))
+            .filter(|w| {
+                self.output
+                    .as_ref()
+                    .is_none_or(|output| w.outputs().contains(output))
+            })
+            .cloned()
+            .collect();
+
+        if shown == self.workspaces {
+            return;
+        }
+
+        let mut previous: Vec<_> = std::mem::take(&mut self.workspaces)
+            .into_iter()
+            .map(|w| w.id())
+            .zip(std::mem::take(&mut self.buttons))
+            .collect();
+
+        for workspace in &shown {
+            let mut button = match previous.iter().position(|(id, _)| *id == workspace.id()) {
+                Some(index) => previous.swap_remove(index).1,
+                None => WorkspaceButton::new(),
+            };
+
+            let text = button.content_mut();
+            text.size = self.text_size;
+            text.color = self.text_color;
+            text.set_text(workspace.name());
+            self.buttons.push(button);
+        }
+
+        self.workspaces = shown;
+        sender.request_redraw();
+    }
+
+    /// Workspace `step` positions away from the active one, without wrapping around.
+    fn neighbour(&self, step: i32) -> Option<&Workspace> {
+        let active = self
+            .workspaces
+            .iter()
+            .position(|w| w.state().contains(WorkspaceState::Active))?;
+
+        let index = active.checked_add_signed(step as isize)?;
+        self.workspaces.get(index)
+    }
+}
+
+impl<C, ID> Widget<C> for Workspaces<C, ID>
+where
+    C: Context,
+    ID: WidgetID,
+{
+    fn anchor(&self) -> Anchor {
+        self.anchor
+    }
+
+    fn desired_size(&self) -> DesiredSize {
+        let count = self.buttons.len() as f32;
+        DesiredSize::Exact(Vec2::new(
+            (self.button_size.x * count + self.spacing * (count - 1.0)).max(0.0),
+            self.button_size.y,
+        ))
+    }
+
+    fn draw<'frame>(&'frame self, out: &mut CommandBuffer<'frame>) {
+        for button in &self.buttons {
+            button.draw(out);
+        }
+    }
+
+    fn layout(&mut self, bounds: Bounds) {
+        self.bounds = bounds;
+
+        let mut position = self.bounds.position;
+        for (workspace, button) in self.workspaces.iter().zip(&mut self.buttons) {
+            let state = workspace.state();
+            button.size = self.button_size;
+            button.normal = if state.contains(WorkspaceState::Urgent) {
+                self.urgent.clone()
+            } else if state.contains(WorkspaceState::Active) {
+                self.active.clone()
+            } else {
+                self.normal.clone()
+            };
+
+            button.layout(Bounds {
+                position,
+                size: self.button_size,
+            });
+            position.x += self.button_size.x + self.spacing;
+        }
+    }
+
+    fn update(&mut self, ctx: &FrameContext, sender: &mut Sender<C>) {
+        self.sync(ctx, sender);
+
+        for (workspace, button) in self.workspaces.iter().zip(&mut self.buttons) {
+            button.update(ctx, sender);
+            if button.clicked() && !workspace.state().contains(WorkspaceState::Active) {
+                sender.activate_workspace(workspace.id());
+            }
+        }
+
+        let step = ctx.scroll().discrete().y;
+        if step != 0
+            && self.bounds.contains(ctx.position())
+            && let Some(workspace) = self.neighbour(step.signum())
+        {
+            sender.activate_workspace(workspace.id());
+        }
+    }
+}
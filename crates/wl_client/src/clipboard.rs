@@ -0,0 +1,519 @@
+use std::{
+    fs::File,
+    io::{Read, Write},
+    os::fd::{AsFd, OwnedFd},
+    sync::{Arc, Mutex},
+};
+
+use calloop::channel::{self, Channel, Sender};
+
+use glam::Vec2;
+use wayland_client::{
+    event_created_child,
+    protocol::{
+        wl_data_device::{self, Event as WlDataDeviceEvent, WlDataDevice},
+        wl_data_device_manager::{self, DndAction, WlDataDeviceManager},
+        wl_data_offer::{Event as WlDataOfferEvent, WlDataOffer},
+        wl_data_source::{Event as WlDataSourceEvent, WlDataSource},
+        wl_seat::WlSeat,
+    },
+    Connection, Dispatch, Proxy, QueueHandle, WEnum,
+};
+use wayland_protocols::wp::primary_selection::zv1::client::{
+    zwp_primary_selection_device_manager_v1::{self, ZwpPrimarySelectionDeviceManagerV1},
+    zwp_primary_selection_device_v1::{
+        self, Event as ZwpPrimarySelectionDeviceV1Event, ZwpPrimarySelectionDeviceV1,
+    },
+    zwp_primary_selection_offer_v1::{
+        Event as ZwpPrimarySelectionOfferV1Event, ZwpPrimarySelectionOfferV1,
+    },
+    zwp_primary_selection_source_v1::{
+        Event as ZwpPrimarySelectionSourceV1Event, ZwpPrimarySelectionSourceV1,
+    },
+};
+
+use crate::{window::WindowId, WlClient};
+
+/// MIME types text is offered as, the first one is preferred when reading.
+pub const TEXT_MIME_TYPES: [&str; 5] = [
+    "text/plain;charset=utf-8",
+    "text/plain",
+    "UTF8_STRING",
+    "STRING",
+    "TEXT",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SelectionKind {
+    /// Written by copy and read by paste.
+    Clipboard,
+    /// Text selected with the pointer, usually pasted with a middle click.
+    Primary,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipboardEvent {
+    /// The selection changed, with the MIME types it can be read as. Empty once cleared.
+    Offered(SelectionKind, Vec<String>),
+    /// Contents read with [`WlClient::read_selection`], with their MIME type.
+    Received(SelectionKind, String, Vec<u8>),
+}
+
+/// MIME types announced by the owner of an offer.
+#[derive(Default, Debug)]
+pub(crate) struct OfferData {
+    mime_types: Mutex<Vec<String>>,
+    /// Action the compositor chose for a drag, `None` until it chooses one.
+    action: Mutex<Option<DndAction>>,
+}
+
+impl OfferData {
+    pub(crate) fn mime_types(&self) -> Vec<String> {
+        self.mime_types.lock().unwrap().clone()
+    }
+
+    pub(crate) fn action(&self) -> Option<DndAction> {
+        *self.action.lock().unwrap()
+    }
+}
+
+/// Contents written to the clients reading a selection or drag we own.
+#[derive(Debug)]
+pub(crate) struct SourceData {
+    data: Arc<[u8]>,
+}
+
+impl SourceData {
+    pub(crate) fn new(data: Vec<u8>) -> Self {
+        Self { data: data.into() }
+    }
+}
+
+pub struct Clipboard {
+    data_device: Option<WlDataDevice>,
+    primary_device: Option<ZwpPrimarySelectionDeviceV1>,
+    /// Current selections, set by any client including us.
+    selection: Option<WlDataOffer>,
+    primary: Option<ZwpPrimarySelectionOfferV1>,
+    /// Selections we own until another client replaces them.
+    source: Option<WlDataSource>,
+    primary_source: Option<ZwpPrimarySelectionSourceV1>,
+
+    events: Vec<ClipboardEvent>,
+    /// Contents read on background threads, a client may take a while to write them.
+    sender: Sender<ClipboardEvent>,
+    /// Wakes the event loop once a read finished, see [`WlClient::insert_sources`].
+    channel: Option<Channel<ClipboardEvent>>,
+}
+
+impl Default for Clipboard {
+    fn default() -> Self {
+        let (sender, channel) = channel::channel();
+        Self {
+            data_device: None,
+            primary_device: None,
+            selection: None,
+            primary: None,
+            source: None,
+            primary_source: None,
+            events: vec![],
+            sender,
+            channel: Some(channel),
+        }
+    }
+}
+
+impl Clipboard {
+    /// MIME types the current selection can be read as.
+    #[must_use]
+    pub fn mime_types(&self, kind: SelectionKind) -> Vec<String> {
+        let data = match kind {
+            SelectionKind::Clipboard => self.selection.as_ref().and_then(|o| o.data()),
+            SelectionKind::Primary => self.primary.as_ref().and_then(|o| o.data()),
+        };
+
+        data.map(OfferData::mime_types).unwrap_or_default()
+    }
+
+    /// Returns the selection changes and contents received since the previous call.
+    pub fn take_events(&mut self) -> Vec<ClipboardEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub(crate) fn take_channel(&mut self) -> Option<Channel<ClipboardEvent>> {
+        self.channel.take()
+    }
+
+    pub(crate) fn push_event(&mut self, event: ClipboardEvent) {
+        self.events.push(event);
+    }
+
+    pub(crate) fn data_device(&self) -> Option<&WlDataDevice> {
+        self.data_device.as_ref()
+    }
+
+    pub(crate) fn init_devices(
+        &mut self,
+        qh: &QueueHandle<WlClient>,
+        seat: &WlSeat,
+        manager: Option<&WlDataDeviceManager>,
+        primary_manager: Option<&ZwpPrimarySelectionDeviceManagerV1>,
+    ) {
+        if self.data_device.is_none()
+            && let Some(manager) = manager
+        {
+            self.data_device = Some(manager.get_data_device(seat, qh, WindowId::default()));
+        }
+
+        if self.primary_device.is_none()
+            && let Some(manager) = primary_manager
+        {
+            self.primary_device = Some(manager.get_device(seat, qh, WindowId::default()));
+        }
+    }
+
+    fn offer(&mut self, kind: SelectionKind, mime_types: Vec<String>) {
+        self.events.push(ClipboardEvent::Offered(kind, mime_types));
+    }
+}
+
+impl WlClient {
+    /// Takes ownership of the selection, offering `data` as each of the `mime_types`.
+    ///
+    /// Tied to the latest press, compositors ignore it when the app has no focus.
+    pub fn set_selection(
+        &mut self,
+        qh: &QueueHandle<WlClient>,
+        kind: SelectionKind,
+        mime_types: &[String],
+        data: Vec<u8>,
+    ) {
+        let data = SourceData::new(data);
+        let clipboard = &mut self.clipboard;
+        match kind {
+            SelectionKind::Clipboard => {
+                let (Some(manager), Some(device)) =
+                    (&self.data_device_manager, &clipboard.data_device)
+                else {
+                    return;
+                };
+
+                let source = manager.create_data_source(qh, data);
+                mime_types.iter().for_each(|m| source.offer(m.clone()));
+                device.set_selection(Some(&source), self.serial);
+
+                if let Some(previous) = clipboard.source.replace(source) {
+                    previous.destroy();
+                }
+            }
+            SelectionKind::Primary => {
+                let (Some(manager), Some(device)) =
+                    (&self.primary_selection_manager, &clipboard.primary_device)
+                else {
+                    return;
+                };
+
+                let source = manager.create_source(qh, data);
+                mime_types.iter().for_each(|m| source.offer(m.clone()));
+                device.set_selection(Some(&source), self.serial);
+
+                if let Some(previous) = clipboard.primary_source.replace(source) {
+                    previous.destroy();
+                }
+            }
+        }
+    }
+
+    pub fn clear_selection(&mut self, kind: SelectionKind) {
+        let clipboard = &mut self.clipboard;
+        match kind {
+            SelectionKind::Clipboard => {
+                if let Some(device) = &clipboard.data_device {
+                    device.set_selection(None, self.serial);
+                }
+
+                if let Some(source) = clipboard.source.take() {
+                    source.destroy();
+                }
+            }
+            SelectionKind::Primary => {
+                if let Some(device) = &clipboard.primary_device {
+                    device.set_selection(None, self.serial);
+                }
+
+                if let Some(source) = clipboard.primary_source.take() {
+                    source.destroy();
+                }
+            }
+        }
+    }
+
+    /// Reads the selection as `mime_type`, the contents are received as a
+    /// [`ClipboardEvent::Received`] once its owner has written them.
+    ///
+    /// Does nothing when the selection is empty or can't be read as `mime_type`.
+    pub fn read_selection(&self, kind: SelectionKind, mime_type: &str) {
+        let clipboard = &self.clipboard;
+        if !clipboard.mime_types(kind).iter().any(|m| m == mime_type) {
+            return;
+        }
+
+        let Ok((mut reader, writer)) = std::io::pipe() else {
+            return;
+        };
+
+        match kind {
+            SelectionKind::Clipboard => {
+                let Some(offer) = &clipboard.selection else {
+                    return;
+                };
+                offer.receive(mime_type.to_string(), writer.as_fd());
+            }
+            SelectionKind::Primary => {
+                let Some(offer) = &clipboard.primary else {
+                    return;
+                };
+                offer.receive(mime_type.to_string(), writer.as_fd());
+            }
+        }
+
+        // The request holds its own copy, the owner closing it ends the read
+        drop(writer);
+
+        let sender = clipboard.sender.clone();
+        let mime_type = mime_type.to_string();
+        std::thread::spawn(move || {
+            let mut data = vec![];
+            if reader.read_to_end(&mut data).is_ok() {
+                let _ = sender.send(ClipboardEvent::Received(kind, mime_type, data));
+            }
+        });
+    }
+
+    #[must_use]
+    pub fn clipboard(&self) -> &Clipboard {
+        &self.clipboard
+    }
+
+    pub fn clipboard_mut(&mut self) -> &mut Clipboard {
+        &mut self.clipboard
+    }
+}
+
+/// Writes our selection to a reading client without blocking the event loop.
+fn write_selection(data: &SourceData, fd: OwnedFd) {
+    let data = data.data.clone();
+    std::thread::spawn(move || {
+        let _ = File::from(fd).write_all(&data);
+    });
+}
+
+impl Dispatch<WlDataDeviceManager, WindowId> for WlClient {
+    fn event(
+        _: &mut Self,
+        _: &WlDataDeviceManager,
+        _: wl_data_device_manager::Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WlDataDevice, WindowId> for WlClient {
+    fn event(
+        client: &mut Self,
+        _: &WlDataDevice,
+        event: WlDataDeviceEvent,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let clipboard = &mut client.clipboard;
+        match event {
+            WlDataDeviceEvent::Selection { id } => {
+                let mime_types = id
+                    .as_ref()
+                    .and_then(|o| o.data::<OfferData>())
+                    .map(OfferData::mime_types)
+                    .unwrap_or_default();
+
+                if let Some(previous) = std::mem::replace(&mut clipboard.selection, id) {
+                    previous.destroy();
+                }
+
+                clipboard.offer(SelectionKind::Clipboard, mime_types);
+            }
+            WlDataDeviceEvent::Enter {
+                serial,
+                surface,
+                x,
+                y,
+                id,
+            } => {
+                // Dragged over a surface without a window, e.g. the drag icon
+                let Some(window) = surface.data::<WindowId>().filter(|w| !w.is_empty()) else {
+                    if let Some(offer) = id {
+                        offer.destroy();
+                    }
+                    return;
+                };
+
+                let position = Vec2::new(x as f32, y as f32);
+                client.dnd.enter(serial, window.clone(), position, id);
+            }
+            WlDataDeviceEvent::Motion { time: _, x, y } => {
+                client.dnd.motion(Vec2::new(x as f32, y as f32));
+            }
+            WlDataDeviceEvent::Leave => client.dnd.leave(),
+            WlDataDeviceEvent::Drop => client.dnd.dropped(),
+            _ => {}
+        }
+    }
+
+    event_created_child!(WlClient, WlDataDevice, [
+        wl_data_device::EVT_DATA_OFFER_OPCODE => (WlDataOffer, OfferData::default()),
+    ]);
+}
+
+impl Dispatch<WlDataOffer, OfferData> for WlClient {
+    fn event(
+        _: &mut Self,
+        _: &WlDataOffer,
+        event: WlDataOfferEvent,
+        data: &OfferData,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            WlDataOfferEvent::Offer { mime_type } => {
+                data.mime_types.lock().unwrap().push(mime_type);
+            }
+            WlDataOfferEvent::Action {
+                dnd_action: WEnum::Value(action),
+            } => {
+                *data.action.lock().unwrap() = Some(action);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<WlDataSource, SourceData> for WlClient {
+    fn event(
+        client: &mut Self,
+        source: &WlDataSource,
+        event: WlDataSourceEvent,
+        data: &SourceData,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if client.dnd.is_source(source) {
+            match event {
+                WlDataSourceEvent::Send { mime_type: _, fd } => write_selection(data, fd),
+                WlDataSourceEvent::Action {
+                    dnd_action: WEnum::Value(action),
+                } => client.dnd.set_source_action(action),
+                WlDataSourceEvent::DndFinished => client.dnd.finish_source(false),
+                WlDataSourceEvent::Cancelled => client.dnd.finish_source(true),
+                _ => {}
+            }
+            return;
+        }
+
+        match event {
+            WlDataSourceEvent::Send { mime_type: _, fd } => write_selection(data, fd),
+            // Replaced by another selection
+            WlDataSourceEvent::Cancelled => {
+                if client.clipboard.source.as_ref() == Some(source) {
+                    client.clipboard.source = None;
+                }
+
+                source.destroy();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ZwpPrimarySelectionDeviceManagerV1, WindowId> for WlClient {
+    fn event(
+        _: &mut Self,
+        _: &ZwpPrimarySelectionDeviceManagerV1,
+        _: zwp_primary_selection_device_manager_v1::Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwpPrimarySelectionDeviceV1, WindowId> for WlClient {
+    fn event(
+        client: &mut Self,
+        _: &ZwpPrimarySelectionDeviceV1,
+        event: ZwpPrimarySelectionDeviceV1Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let clipboard = &mut client.clipboard;
+        if let ZwpPrimarySelectionDeviceV1Event::Selection { id } = event {
+            let mime_types = id
+                .as_ref()
+                .and_then(|o| o.data::<OfferData>())
+                .map(OfferData::mime_types)
+                .unwrap_or_default();
+
+            if let Some(previous) = std::mem::replace(&mut clipboard.primary, id) {
+                previous.destroy();
+            }
+
+            clipboard.offer(SelectionKind::Primary, mime_types);
+        }
+    }
+
+    event_created_child!(WlClient, ZwpPrimarySelectionDeviceV1, [
+        zwp_primary_selection_device_v1::EVT_DATA_OFFER_OPCODE => (ZwpPrimarySelectionOfferV1, OfferData::default()),
+    ]);
+}
+
+impl Dispatch<ZwpPrimarySelectionOfferV1, OfferData> for WlClient {
+    fn event(
+        _: &mut Self,
+        _: &ZwpPrimarySelectionOfferV1,
+        event: ZwpPrimarySelectionOfferV1Event,
+        data: &OfferData,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let ZwpPrimarySelectionOfferV1Event::Offer { mime_type } = event {
+            data.mime_types.lock().unwrap().push(mime_type);
+        }
+    }
+}
+
+impl Dispatch<ZwpPrimarySelectionSourceV1, SourceData> for WlClient {
+    fn event(
+        client: &mut Self,
+        source: &ZwpPrimarySelectionSourceV1,
+        event: ZwpPrimarySelectionSourceV1Event,
+        data: &SourceData,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            ZwpPrimarySelectionSourceV1Event::Send { mime_type: _, fd } => {
+                write_selection(data, fd);
+            }
+            ZwpPrimarySelectionSourceV1Event::Cancelled => {
+                if client.clipboard.primary_source.as_ref() == Some(source) {
+                    client.clipboard.primary_source = None;
+                }
+
+                source.destroy();
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use wayland_client::{
+    protocol::{
+        wl_compositor::WlCompositor, wl_pointer::WlPointer, wl_shm::WlShm, wl_surface::WlSurface,
+    },
+    Connection, Dispatch, QueueHandle,
+};
+use wayland_cursor::CursorTheme;
+use wayland_protocols::wp::cursor_shape::v1::client::{
+    wp_cursor_shape_device_v1::{self, WpCursorShapeDeviceV1},
+    wp_cursor_shape_manager_v1::{self, WpCursorShapeManagerV1},
+};
+
+pub use wayland_protocols::wp::cursor_shape::v1::client::wp_cursor_shape_device_v1::Shape as CursorShape;
+
+use crate::{window::WindowId, WlClient};
+
+/// Size of the themed cursors when `XCURSOR_SIZE` is not set.
+const DEFAULT_CURSOR_SIZE: u32 = 24;
+
+/// xcursor theme loaded at the scale of the window the pointer is over.
+struct Themed {
+    conn: Connection,
+    shm: WlShm,
+    theme: CursorTheme,
+    scale: i32,
+    surface: WlSurface,
+}
+
+impl Themed {
+    fn load(conn: &Connection, shm: &WlShm, scale: i32) -> Option<CursorTheme> {
+        let name = std::env::var("XCURSOR_THEME").unwrap_or_else(|_| "default".to_string());
+        let size = std::env::var("XCURSOR_SIZE")
+            .ok()
+            .and_then(|size| size.parse().ok())
+            .unwrap_or(DEFAULT_CURSOR_SIZE);
+
+        CursorTheme::load_from_name(conn, shm.clone(), &name, size * scale as u32).ok()
+    }
+}
+
+/// Pointer image, set with `wp_cursor_shape_v1` when the compositor supports it and
+/// drawn from the xcursor theme to a surface otherwise.
+#[derive(Default)]
+pub(crate) struct Cursor {
+    device: Option<WpCursorShapeDeviceV1>,
+    themed: Option<Themed>,
+    /// Serial of the latest `wl_pointer.enter`, the cursor can only be set for that enter.
+    serial: u32,
+    /// Integer scale of the window entered, themed cursors are drawn at it.
+    scale: i32,
+    /// Shown since that enter, the compositor doesn't keep it between surfaces.
+    current: Option<CursorShape>,
+}
+
+impl Cursor {
+    pub(crate) fn new(
+        conn: &Connection,
+        qh: &QueueHandle<WlClient>,
+        pointer: &WlPointer,
+        manager: Option<&WpCursorShapeManagerV1>,
+        compositor: &WlCompositor,
+        shm: &WlShm,
+    ) -> Self {
+        let id = WindowId::default();
+        if let Some(manager) = manager {
+            return Self {
+                device: Some(manager.get_pointer(pointer, qh, id)),
+                scale: 1,
+                ..Self::default()
+            };
+        }
+
+        let themed = Themed::load(conn, shm, 1).map(|theme| Themed {
+            conn: conn.clone(),
+            shm: shm.clone(),
+            theme,
+            scale: 1,
+            surface: compositor.create_surface(qh, id),
+        });
+
+        Self {
+            themed,
+            scale: 1,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn enter(&mut self, serial: u32, scale: i32) {
+        self.serial = serial;
+        self.scale = scale.max(1);
+        self.current = None;
+    }
+
+    pub(crate) fn set(&mut self, pointer: &WlPointer, shape: CursorShape) {
+        if self.current == Some(shape) {
+            return;
+        }
+
+        self.current = Some(shape);
+        if let Some(device) = &self.device {
+            device.set_shape(self.serial, shape);
+            return;
+        }
+
+        let Some(themed) = &mut self.themed else {
+            return;
+        };
+
+        if themed.scale != self.scale
+            && let Some(theme) = Themed::load(&themed.conn, &themed.shm, self.scale)
+        {
+            themed.theme = theme;
+            themed.scale = self.scale;
+        }
+
+        // Older themes only ship the X11 names
+        let theme = &mut themed.theme;
+        let Some(cursor) = [xcursor_name(shape), "default", "left_ptr"]
+            .into_iter()
+            .find_map(|name| theme.get_cursor(name).map(|_| name))
+            .and_then(|name| theme.get_cursor(name))
+        else {
+            return;
+        };
+
+        // Animated cursors keep their first frame
+        let image = &cursor[0];
+        let (width, height) = image.dimensions();
+        let (x, y) = image.hotspot();
+
+        // Themes without the scaled size give the nearest one, which may not divide evenly
+        let scale = themed.scale as u32;
+        let scale = if width % scale == 0 && height % scale == 0 {
+            scale
+        } else {
+            1
+        };
+
+        let surface = &themed.surface;
+        pointer.set_cursor(
+            self.serial,
+            Some(surface),
+            (x / scale) as i32,
+            (y / scale) as i32,
+        );
+        surface.set_buffer_scale(scale as i32);
+        surface.attach(Some(&**image), 0, 0);
+        surface.damage_buffer(0, 0, width as i32, height as i32);
+        surface.commit();
+    }
+
+    pub(crate) fn destroy(self) {
+        if let Some(device) = self.device {
+            device.destroy();
+        }
+
+        if let Some(themed) = self.themed {
+            themed.surface.destroy();
+        }
+    }
+}
+
+/// Name of the shape in cursor themes, the same as the CSS cursor keyword.
+fn xcursor_name(shape: CursorShape) -> &'static str {
+    match shape {
+        CursorShape::ContextMenu => "context-menu",
+        CursorShape::Help => "help",
+        CursorShape::Pointer => "pointer",
+        CursorShape::Progress => "progress",
+        CursorShape::Wait => "wait",
+        CursorShape::Cell => "cell",
+        CursorShape::Crosshair => "crosshair",
+        CursorShape::Text => "text",
+        CursorShape::VerticalText => "vertical-text",
+        CursorShape::Alias => "alias",
+        CursorShape::Copy => "copy",
+        CursorShape::Move => "move",
+        CursorShape::NoDrop => "no-drop",
+        CursorShape::NotAllowed => "not-allowed",
+        CursorShape::Grab => "grab",
+        CursorShape::Grabbing => "grabbing",
+        CursorShape::EResize => "e-resize",
+        CursorShape::NResize => "n-resize",
+        CursorShape::NeResize => "ne-resize",
+        CursorShape::NwResize => "nw-resize",
+        CursorShape::SResize => "s-resize",
+        CursorShape::SeResize => "se-resize",
+        CursorShape::SwResize => "sw-resize",
+        CursorShape::WResize => "w-resize",
+        CursorShape::EwResize => "ew-resize",
+        CursorShape::NsResize => "ns-resize",
+        CursorShape::NeswResize => "nesw-resize",
+        CursorShape::NwseResize => "nwse-resize",
+        CursorShape::ColResize => "col-resize",
+        CursorShape::RowResize => "row-resize",
+        CursorShape::AllScroll => "all-scroll",
+        CursorShape::ZoomIn => "zoom-in",
+        CursorShape::ZoomOut => "zoom-out",
+        _ => "default",
+    }
+}
+
+impl Dispatch<WpCursorShapeManagerV1, WindowId> for WlClient {
+    fn event(
+        _: &mut Self,
+        _: &WpCursorShapeManagerV1,
+        _: wp_cursor_shape_manager_v1::Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WpCursorShapeDeviceV1, WindowId> for WlClient {
+    fn event(
+        _: &mut Self,
+        _: &WpCursorShapeDeviceV1,
+        _: wp_cursor_shape_device_v1::Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
@@ -0,0 +1,413 @@
+use std::{io::Read, os::fd::AsFd};
+
+use calloop::channel::{self, Channel, Sender};
+
+use glam::{IVec2, Vec2};
+use wayland_client::{
+    protocol::{
+        wl_buffer::WlBuffer, wl_data_offer::WlDataOffer, wl_data_source::WlDataSource,
+        wl_surface::WlSurface,
+    },
+    Proxy, QueueHandle,
+};
+
+pub use wayland_client::protocol::wl_data_device_manager::DndAction;
+
+use crate::{
+    clipboard::{OfferData, SourceData},
+    window::{ShmPool, WindowId},
+    WlClient,
+};
+
+/// Drag-and-drop actions, `set_actions` and `finish` were introduced in version 3.
+const ACTIONS_SINCE: u32 = 3;
+/// `wl_surface.offset` was introduced in version 5.
+const OFFSET_SINCE: u32 = 5;
+
+/// Image shown under the pointer while dragging.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DragIcon {
+    pub width: u32,
+    pub height: u32,
+    /// Premultiplied `Argb8888` pixels row by row, i.e. `[b, g, r, a]` bytes.
+    pub pixels: Vec<u8>,
+    /// Position of the pointer in the icon.
+    pub hotspot: IVec2,
+}
+
+/// Drag from another client or one of our windows, over one of our windows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DragOffer {
+    window: WindowId,
+    position: Vec2,
+    mime_types: Vec<String>,
+}
+
+impl DragOffer {
+    #[must_use]
+    pub fn window(&self) -> &WindowId {
+        &self.window
+    }
+
+    /// Position in the surface coordinates of the window.
+    #[must_use]
+    pub const fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    #[must_use]
+    pub fn mime_types(&self) -> &[String] {
+        &self.mime_types
+    }
+}
+
+/// Contents of a drag dropped on one of our windows, in the MIME type accepted last.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DroppedData {
+    window: WindowId,
+    position: Vec2,
+    mime_type: String,
+    data: Vec<u8>,
+}
+
+impl DroppedData {
+    #[must_use]
+    pub fn window(&self) -> &WindowId {
+        &self.window
+    }
+
+    /// Position in the surface coordinates of the window.
+    #[must_use]
+    pub const fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    #[must_use]
+    pub fn mime_type(&self) -> &str {
+        &self.mime_type
+    }
+
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+/// Outcome of a drag we started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DragSourceEvent {
+    /// Dropped and read by the target. With [`DndAction::Move`] the data should be removed
+    /// from where it was dragged.
+    Finished(DndAction),
+    /// Dropped nowhere, rejected, or replaced by another drag.
+    Cancelled,
+}
+
+#[derive(Debug)]
+struct DragSource {
+    source: WlDataSource,
+    icon: Option<(WlSurface, ShmPool, WlBuffer)>,
+    /// Action chosen by the compositor from the ones both sides support.
+    action: DndAction,
+}
+
+impl DragSource {
+    fn destroy(self) {
+        self.source.destroy();
+        if let Some((surface, pool, buffer)) = self.icon {
+            surface.destroy();
+            buffer.destroy();
+            pool.destroy();
+        }
+    }
+}
+
+pub struct DragAndDrop {
+    offer: Option<WlDataOffer>,
+    /// Serial of the `wl_data_device.enter` the offer came with, required to accept it.
+    serial: u32,
+    current: Option<DragOffer>,
+    accepted: Option<String>,
+    source: Option<DragSource>,
+
+    events: Vec<DragSourceEvent>,
+    drops: Vec<DroppedData>,
+    /// Drops read on background threads, with the offers to finish once they are.
+    sender: Sender<(DroppedData, WlDataOffer)>,
+    /// Wakes the event loop once a drop is read, see [`WlClient::insert_sources`].
+    channel: Option<Channel<(DroppedData, WlDataOffer)>>,
+}
+
+impl Default for DragAndDrop {
+    fn default() -> Self {
+        let (sender, channel) = channel::channel();
+        Self {
+            offer: None,
+            serial: 0,
+            current: None,
+            accepted: None,
+            source: None,
+            events: vec![],
+            drops: vec![],
+            sender,
+            channel: Some(channel),
+        }
+    }
+}
+
+impl DragAndDrop {
+    /// Drag over one of our windows.
+    #[must_use]
+    pub fn current(&self) -> Option<&DragOffer> {
+        self.current.as_ref()
+    }
+
+    /// Returns the drops read since the previous call.
+    pub fn take_drops(&mut self) -> Vec<DroppedData> {
+        std::mem::take(&mut self.drops)
+    }
+
+    pub(crate) fn take_channel(&mut self) -> Option<Channel<(DroppedData, WlDataOffer)>> {
+        self.channel.take()
+    }
+
+    /// Tells the source the drop was read, as soon as it is.
+    ///
+    /// Finishing is an error unless the compositor chose an action, the offer is then only
+    /// destroyed.
+    pub(crate) fn finish_drop(&mut self, drop: DroppedData, offer: WlDataOffer) {
+        let action = offer.data::<OfferData>().and_then(OfferData::action);
+        if offer.version() >= ACTIONS_SINCE && action.is_some_and(|action| !action.is_empty()) {
+            offer.finish();
+        }
+
+        offer.destroy();
+        self.drops.push(drop);
+    }
+
+    /// Returns the outcomes of our drags since the previous call.
+    pub fn take_events(&mut self) -> Vec<DragSourceEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub(crate) fn enter(
+        &mut self,
+        serial: u32,
+        window: WindowId,
+        position: Vec2,
+        offer: Option<WlDataOffer>,
+    ) {
+        self.leave();
+
+        let Some(offer) = offer else {
+            return;
+        };
+
+        if offer.version() >= ACTIONS_SINCE {
+            offer.set_actions(DndAction::Copy | DndAction::Move, DndAction::Copy);
+        }
+
+        self.serial = serial;
+        self.current = Some(DragOffer {
+            window,
+            position,
+            mime_types: offer.data().map(OfferData::mime_types).unwrap_or_default(),
+        });
+        self.offer = Some(offer);
+    }
+
+    pub(crate) fn motion(&mut self, position: Vec2) {
+        if let Some(current) = &mut self.current {
+            current.position = position;
+        }
+    }
+
+    pub(crate) fn leave(&mut self) {
+        if let Some(offer) = self.offer.take() {
+            offer.destroy();
+        }
+
+        self.current = None;
+        self.accepted = None;
+    }
+
+    /// Reads the drop on a background thread, the source may take a while to write it.
+    pub(crate) fn dropped(&mut self) {
+        let (Some(offer), Some(current), Some(mime_type)) =
+            (self.offer.take(), self.current.take(), self.accepted.take())
+        else {
+            self.leave();
+            return;
+        };
+
+        let Ok((mut reader, writer)) = std::io::pipe() else {
+            offer.destroy();
+            return;
+        };
+
+        offer.receive(mime_type.clone(), writer.as_fd());
+        drop(writer);
+
+        let sender = self.sender.clone();
+        std::thread::spawn(move || {
+            let mut data = vec![];
+            if reader.read_to_end(&mut data).is_ok() {
+                let drop = DroppedData {
+                    window: current.window,
+                    position: current.position,
+                    mime_type,
+                    data,
+                };
+
+                let _ = sender.send((drop, offer));
+            }
+        });
+    }
+
+    pub(crate) fn is_source(&self, source: &WlDataSource) -> bool {
+        self.source
+            .as_ref()
+            .is_some_and(|drag| drag.source == *source)
+    }
+
+    pub(crate) fn set_source_action(&mut self, action: DndAction) {
+        if let Some(drag) = &mut self.source {
+            drag.action = action;
+        }
+    }
+
+    pub(crate) fn finish_source(&mut self, cancelled: bool) {
+        let Some(drag) = self.source.take() else {
+            return;
+        };
+
+        self.events.push(if cancelled {
+            DragSourceEvent::Cancelled
+        } else {
+            DragSourceEvent::Finished(drag.action)
+        });
+
+        drag.destroy();
+    }
+}
+
+impl WlClient {
+    /// Accepts the drag over our window as `mime_type`, or rejects it with `None`.
+    ///
+    /// Only the MIME type accepted last is read on drop.
+    pub fn accept_drag(&mut self, mime_type: Option<&str>) {
+        let dnd = &mut self.dnd;
+        if dnd.accepted.as_deref() == mime_type {
+            return;
+        }
+
+        let Some(offer) = &dnd.offer else {
+            return;
+        };
+
+        dnd.accepted = mime_type.map(ToString::to_string);
+        offer.accept(dnd.serial, dnd.accepted.clone());
+    }
+
+    /// Starts dragging `data` offered as each of the `mime_types` from the window `id`.
+    ///
+    /// Tied to the latest press, which must still be held.
+    pub fn start_drag(
+        &mut self,
+        qh: &QueueHandle<WlClient>,
+        id: &str,
+        mime_types: &[String],
+        data: Vec<u8>,
+        icon: Option<&DragIcon>,
+    ) {
+        let (Some(manager), Some(device), Some(window)) = (
+            &self.data_device_manager,
+            self.clipboard.data_device(),
+            self.windows.get(id),
+        ) else {
+            return;
+        };
+
+        let source = manager.create_data_source(qh, SourceData::new(data));
+        mime_types.iter().for_each(|m| source.offer(m.clone()));
+        if source.version() >= ACTIONS_SINCE {
+            source.set_actions(DndAction::Copy | DndAction::Move);
+        }
+
+        let drag_icon = icon.and_then(|icon| self.create_drag_icon(qh, icon));
+        device.start_drag(
+            Some(&source),
+            window.lock().unwrap().surface(),
+            drag_icon.as_ref().map(|(surface, ..)| surface),
+            self.serial,
+        );
+
+        // The icon surface gets its role from the drag, its buffer must come after
+        if let (Some((surface, _, buffer)), Some(icon)) = (&drag_icon, icon) {
+            show_drag_icon(surface, buffer, icon);
+        }
+
+        let previous = self.dnd.source.replace(DragSource {
+            source,
+            icon: drag_icon,
+            action: DndAction::empty(),
+        });
+
+        if let Some(previous) = previous {
+            self.dnd.events.push(DragSourceEvent::Cancelled);
+            previous.destroy();
+        }
+    }
+
+    fn create_drag_icon(
+        &self,
+        qh: &QueueHandle<WlClient>,
+        icon: &DragIcon,
+    ) -> Option<(WlSurface, ShmPool, WlBuffer)> {
+        let (Some(compositor), Some(shm)) = (&self.compositor, &self.shm) else {
+            return None;
+        };
+
+        let id = WindowId::default();
+        let (width, height) = (icon.width as usize, icon.height as usize);
+        if width == 0 || height == 0 || icon.pixels.len() < width * height * 4 {
+            return None;
+        }
+
+        let mut pool = ShmPool::new((width * height * 4) as u64, &id, shm, qh);
+        for (i, pixel) in icon.pixels.chunks_exact(4).take(width * height).enumerate() {
+            pool.write_pixel(
+                i % width,
+                i / width,
+                width,
+                (pixel[0], pixel[1], pixel[2], pixel[3]),
+            );
+        }
+
+        let buffer = pool.create_buffer(0, width as i32, height as i32, qh, &id);
+        let surface = compositor.create_surface(qh, id);
+
+        Some((surface, pool, buffer))
+    }
+
+    #[must_use]
+    pub fn dnd(&self) -> &DragAndDrop {
+        &self.dnd
+    }
+
+    pub fn dnd_mut(&mut self) -> &mut DragAndDrop {
+        &mut self.dnd
+    }
+}
+
+fn show_drag_icon(surface: &WlSurface, buffer: &WlBuffer, icon: &DragIcon) {
+    // Attaching with an offset is an error since `wl_surface.offset` was added
+    if surface.version() >= OFFSET_SINCE {
+        surface.offset(-icon.hotspot.x, -icon.hotspot.y);
+        surface.attach(Some(buffer), 0, 0);
+    } else {
+        surface.attach(Some(buffer), -icon.hotspot.x, -icon.hotspot.y);
+    }
+    surface.damage_buffer(0, 0, icon.width as i32, icon.height as i32);
+    surface.commit();
+}
@@ -0,0 +1,406 @@
+use bitflags::bitflags;
+use smithay_client_toolkit::reexports::protocols_wlr::foreign_toplevel::v1::client::{
+    zwlr_foreign_toplevel_handle_v1::{
+        Event as ZwlrForeignToplevelHandleV1Event, State, ZwlrForeignToplevelHandleV1,
+    },
+    zwlr_foreign_toplevel_manager_v1::{
+        self, Event as ZwlrForeignToplevelManagerV1Event, ZwlrForeignToplevelManagerV1,
+    },
+};
+use wayland_client::{event_created_child, Connection, Dispatch, QueueHandle};
+use wayland_protocols::ext::foreign_toplevel_list::v1::client::{
+    ext_foreign_toplevel_handle_v1::{
+        Event as ExtForeignToplevelHandleV1Event, ExtForeignToplevelHandleV1,
+    },
+    ext_foreign_toplevel_list_v1::{
+        self, Event as ExtForeignToplevelListV1Event, ExtForeignToplevelListV1,
+    },
+};
+
+use crate::{window::WindowId, WlClient};
+
+bitflags! {
+    /// State of a toplevel of any client, only known with `zwlr_foreign_toplevel_manager_v1`.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ForeignToplevelState: u8 {
+        const Maximized  = 1 << 0;
+        const Minimized  = 1 << 1;
+        const Activated  = 1 << 2;
+        const Fullscreen = 1 << 3;
+    }
+}
+
+impl ForeignToplevelState {
+    /// Parses the `state` array of `zwlr_foreign_toplevel_handle_v1.state`.
+    fn from_array(states: &[u8]) -> Self {
+        states
+            .chunks_exact(4)
+            .map(|chunk| u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .filter_map(|state| State::try_from(state).ok())
+            .fold(Self::empty(), |flags, state| {
+                flags
+                    | match state {
+                        State::Maximized => Self::Maximized,
+                        State::Minimized => Self::Minimized,
+                        State::Activated => Self::Activated,
+                        State::Fullscreen => Self::Fullscreen,
+                        _ => Self::empty(),
+                    }
+            })
+    }
+}
+
+/// Window opened by any client, e.g. for a taskbar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForeignToplevel {
+    id: u64,
+    title: String,
+    app_id: String,
+    state: ForeignToplevelState,
+    outputs: Vec<String>,
+}
+
+impl ForeignToplevel {
+    const fn new(id: u64) -> Self {
+        Self {
+            id,
+            title: String::new(),
+            app_id: String::new(),
+            state: ForeignToplevelState::empty(),
+            outputs: vec![],
+        }
+    }
+
+    /// Identifies the toplevel until it is closed, never reused.
+    #[must_use]
+    pub const fn id(&self) -> u64 {
+        self.id
+    }
+
+    #[must_use]
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Usually the name of the desktop file of the app.
+    #[must_use]
+    pub fn app_id(&self) -> &str {
+        &self.app_id
+    }
+
+    #[must_use]
+    pub const fn state(&self) -> ForeignToplevelState {
+        self.state
+    }
+
+    /// Names of the outputs the toplevel is shown on, see [`Output::name`](crate::output::Output::name).
+    #[must_use]
+    pub fn outputs(&self) -> &[String] {
+        &self.outputs
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForeignToplevelEvent {
+    Added(ForeignToplevel),
+    Changed(ForeignToplevel),
+    Removed(ForeignToplevel),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Handle {
+    Wlr(ZwlrForeignToplevelHandleV1),
+    /// Listed only, the toplevel can't be controlled.
+    Ext(ExtForeignToplevelHandleV1),
+}
+
+#[derive(Debug)]
+struct ToplevelEntry {
+    handle: Handle,
+    info: ForeignToplevel,
+    /// State being received until the next `done`.
+    pending: ForeignToplevel,
+    /// The first `done` was received and [`ForeignToplevelEvent::Added`] raised.
+    announced: bool,
+}
+
+#[derive(Default, Debug)]
+pub struct ForeignToplevels {
+    entries: Vec<ToplevelEntry>,
+    events: Vec<ForeignToplevelEvent>,
+    next_id: u64,
+}
+
+impl ForeignToplevels {
+    /// Announced toplevels in the order the compositor listed them.
+    pub fn iter(&self) -> impl Iterator<Item = &ForeignToplevel> {
+        self.entries.iter().filter(|e| e.announced).map(|e| &e.info)
+    }
+
+    #[must_use]
+    pub fn get(&self, id: u64) -> Option<&ForeignToplevel> {
+        self.iter().find(|t| t.id == id)
+    }
+
+    /// Returns the toplevels added, changed or closed since the previous call.
+    pub fn take_events(&mut self) -> Vec<ForeignToplevelEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn insert(&mut self, handle: Handle) {
+        let toplevel = ForeignToplevel::new(self.next_id);
+        self.next_id += 1;
+        self.entries.push(ToplevelEntry {
+            handle,
+            info: toplevel.clone(),
+            pending: toplevel,
+            announced: false,
+        });
+    }
+
+    fn pending_mut(&mut self, handle: &Handle) -> Option<&mut ForeignToplevel> {
+        self.entries
+            .iter_mut()
+            .find(|e| e.handle == *handle)
+            .map(|e| &mut e.pending)
+    }
+
+    fn commit(&mut self, handle: &Handle) {
+        let Some(entry) = self.entries.iter_mut().find(|e| e.handle == *handle) else {
+            return;
+        };
+
+        if !entry.announced {
+            entry.announced = true;
+            entry.info = entry.pending.clone();
+            self.events
+                .push(ForeignToplevelEvent::Added(entry.info.clone()));
+        } else if entry.info != entry.pending {
+            entry.info = entry.pending.clone();
+            self.events
+                .push(ForeignToplevelEvent::Changed(entry.info.clone()));
+        }
+    }
+
+    fn remove(&mut self, handle: &Handle) {
+        let Some(index) = self.entries.iter().position(|e| e.handle == *handle) else {
+            return;
+        };
+
+        let entry = self.entries.remove(index);
+        match entry.handle {
+            Handle::Wlr(handle) => handle.destroy(),
+            Handle::Ext(handle) => handle.destroy(),
+        }
+
+        if entry.announced {
+            self.events.push(ForeignToplevelEvent::Removed(entry.info));
+        }
+    }
+
+    /// Drops the toplevels of `ext_foreign_toplevel_list_v1` once the wlr manager is bound.
+    pub(crate) fn remove_listed(&mut self) {
+        let listed: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|e| matches!(e.handle, Handle::Ext(_)))
+            .map(|e| e.handle.clone())
+            .collect();
+
+        listed.iter().for_each(|handle| self.remove(handle));
+    }
+
+    fn wlr_handle(&self, id: u64) -> Option<&ZwlrForeignToplevelHandleV1> {
+        self.entries.iter().find_map(|e| match &e.handle {
+            Handle::Wlr(handle) if e.info.id == id => Some(handle),
+            _ => None,
+        })
+    }
+}
+
+impl WlClient {
+    /// Focuses the toplevel, unminimizing it if needed.
+    ///
+    /// Like the other toplevel requests below, ignored when the compositor only supports
+    /// `ext_foreign_toplevel_list_v1`.
+    pub fn activate_toplevel(&self, id: u64) {
+        if let (Some(handle), Some(seat)) = (self.foreign_toplevels.wlr_handle(id), &self.seat) {
+            handle.activate(seat);
+        }
+    }
+
+    pub fn set_toplevel_minimized(&self, id: u64, minimized: bool) {
+        let Some(handle) = self.foreign_toplevels.wlr_handle(id) else {
+            return;
+        };
+
+        if minimized {
+            handle.set_minimized();
+        } else {
+            handle.unset_minimized();
+        }
+    }
+
+    pub fn set_toplevel_maximized(&self, id: u64, maximized: bool) {
+        let Some(handle) = self.foreign_toplevels.wlr_handle(id) else {
+            return;
+        };
+
+        if maximized {
+            handle.set_maximized();
+        } else {
+            handle.unset_maximized();
+        }
+    }
+
+    /// Asks the client of the toplevel to close it, which it may refuse.
+    pub fn close_toplevel(&self, id: u64) {
+        if let Some(handle) = self.foreign_toplevels.wlr_handle(id) {
+            handle.close();
+        }
+    }
+
+    #[must_use]
+    pub fn foreign_toplevels(&self) -> &ForeignToplevels {
+        &self.foreign_toplevels
+    }
+
+    pub fn foreign_toplevels_mut(&mut self) -> &mut ForeignToplevels {
+        &mut self.foreign_toplevels
+    }
+}
+
+impl Dispatch<ZwlrForeignToplevelManagerV1, WindowId> for WlClient {
+    fn event(
+        client: &mut Self,
+        _: &ZwlrForeignToplevelManagerV1,
+        event: ZwlrForeignToplevelManagerV1Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            ZwlrForeignToplevelManagerV1Event::Toplevel { toplevel } => {
+                client.foreign_toplevels.insert(Handle::Wlr(toplevel));
+            }
+            ZwlrForeignToplevelManagerV1Event::Finished => {
+                client.foreign_toplevel_manager = None;
+            }
+            _ => {}
+        }
+    }
+
+    event_created_child!(WlClient, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, WindowId::default()),
+    ]);
+}
+
+impl Dispatch<ZwlrForeignToplevelHandleV1, WindowId> for WlClient {
+    fn event(
+        client: &mut Self,
+        proxy: &ZwlrForeignToplevelHandleV1,
+        event: ZwlrForeignToplevelHandleV1Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let handle = Handle::Wlr(proxy.clone());
+        if let ZwlrForeignToplevelHandleV1Event::Done = event {
+            client.foreign_toplevels.commit(&handle);
+            return;
+        }
+
+        if let ZwlrForeignToplevelHandleV1Event::Closed = event {
+            client.foreign_toplevels.remove(&handle);
+            return;
+        }
+
+        let output_name = match &event {
+            ZwlrForeignToplevelHandleV1Event::OutputEnter { output }
+            | ZwlrForeignToplevelHandleV1Event::OutputLeave { output } => {
+                client.outputs.name_of(output).map(ToString::to_string)
+            }
+            _ => None,
+        };
+
+        let Some(pending) = client.foreign_toplevels.pending_mut(&handle) else {
+            return;
+        };
+
+        match event {
+            ZwlrForeignToplevelHandleV1Event::Title { title } => pending.title = title,
+            ZwlrForeignToplevelHandleV1Event::AppId { app_id } => pending.app_id = app_id,
+            ZwlrForeignToplevelHandleV1Event::State { state } => {
+                pending.state = ForeignToplevelState::from_array(&state);
+            }
+            ZwlrForeignToplevelHandleV1Event::OutputEnter { .. } => {
+                if let Some(name) = output_name
+                    && !pending.outputs.contains(&name)
+                {
+                    pending.outputs.push(name);
+                }
+            }
+            ZwlrForeignToplevelHandleV1Event::OutputLeave { .. } => {
+                pending.outputs.retain(|o| Some(o) != output_name.as_ref());
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ExtForeignToplevelListV1, WindowId> for WlClient {
+    fn event(
+        client: &mut Self,
+        proxy: &ExtForeignToplevelListV1,
+        event: ExtForeignToplevelListV1Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            ExtForeignToplevelListV1Event::Toplevel { toplevel } => {
+                client.foreign_toplevels.insert(Handle::Ext(toplevel));
+            }
+            ExtForeignToplevelListV1Event::Finished => {
+                proxy.destroy();
+                if client.foreign_toplevel_list.as_ref() == Some(proxy) {
+                    client.foreign_toplevel_list = None;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    event_created_child!(WlClient, ExtForeignToplevelListV1, [
+        ext_foreign_toplevel_list_v1::EVT_TOPLEVEL_OPCODE => (ExtForeignToplevelHandleV1, WindowId::default()),
+    ]);
+}
+
+impl Dispatch<ExtForeignToplevelHandleV1, WindowId> for WlClient {
+    fn event(
+        client: &mut Self,
+        proxy: &ExtForeignToplevelHandleV1,
+        event: ExtForeignToplevelHandleV1Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let handle = Handle::Ext(proxy.clone());
+        let toplevels = &mut client.foreign_toplevels;
+        match event {
+            ExtForeignToplevelHandleV1Event::Done => toplevels.commit(&handle),
+            ExtForeignToplevelHandleV1Event::Closed => toplevels.remove(&handle),
+            ExtForeignToplevelHandleV1Event::Title { title } => {
+                if let Some(pending) = toplevels.pending_mut(&handle) {
+                    pending.title = title;
+                }
+            }
+            ExtForeignToplevelHandleV1Event::AppId { app_id } => {
+                if let Some(pending) = toplevels.pending_mut(&handle) {
+                    pending.app_id = app_id;
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use std::time::Duration;
+use wayland_client::{Connection, Dispatch, QueueHandle};
+use wayland_protocols::{
+    ext::idle_notify::v1::client::{
+        ext_idle_notification_v1::{Event as ExtIdleNotificationV1Event, ExtIdleNotificationV1},
+        ext_idle_notifier_v1::{self, ExtIdleNotifierV1},
+    },
+    wp::idle_inhibit::zv1::client::{
+        zwp_idle_inhibit_manager_v1::{self, ZwpIdleInhibitManagerV1},
+        zwp_idle_inhibitor_v1::{self, ZwpIdleInhibitorV1},
+    },
+};
+
+use crate::{window::WindowId, WlClient};
+
+/// Change of activity of the user, for the timeout given to [`WlClient::watch_idle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleEvent {
+    /// No input for the timeout, and no window keeps the session awake.
+    Idled(Duration),
+    /// Input came back after [`IdleEvent::Idled`].
+    Resumed(Duration),
+}
+
+#[derive(Default, Debug)]
+pub struct Idle {
+    /// Windows keeping the screen awake while they are visible.
+    inhibitors: Vec<(WindowId, ZwpIdleInhibitorV1)>,
+    notifications: Vec<(Duration, ExtIdleNotificationV1)>,
+    events: Vec<IdleEvent>,
+}
+
+impl Idle {
+    #[must_use]
+    pub fn inhibited(&self, window_id: &str) -> bool {
+        self.inhibitors.iter().any(|(id, _)| **id == window_id)
+    }
+
+    /// Returns the idle changes received since the previous call.
+    pub fn take_events(&mut self) -> Vec<IdleEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Drops the inhibitor of a window, which must go before its surface.
+    pub(crate) fn remove_inhibitor(&mut self, window_id: &str) {
+        if let Some(index) = self.inhibitors.iter().position(|(id, _)| **id == window_id) {
+            self.inhibitors.remove(index).1.destroy();
+        }
+    }
+}
+
+impl WlClient {
+    /// Keeps the screen from blanking or locking while the window is visible, e.g. during a
+    /// presentation or a video.
+    pub fn set_idle_inhibited(
+        &mut self,
+        qh: &QueueHandle<WlClient>,
+        window_id: &str,
+        inhibited: bool,
+    ) {
+        if !inhibited {
+            self.idle.remove_inhibitor(window_id);
+            return;
+        }
+
+        let (Some(manager), Some(window)) =
+            (&self.idle_inhibit_manager, self.windows.get(window_id))
+        else {
+            return;
+        };
+
+        if self.idle.inhibited(window_id) {
+            return;
+        }
+
+        let window = window.lock().unwrap();
+        let inhibitor = manager.create_inhibitor(window.surface(), qh, window.id.clone());
+        self.idle.inhibitors.push((window.id.clone(), inhibitor));
+    }
+
+    /// Raises [`IdleEvent::Idled`] once the user gave no input for `timeout`, and
+    /// [`IdleEvent::Resumed`] when they come back. Each timeout is watched once.
+    pub fn watch_idle(&mut self, qh: &QueueHandle<WlClient>, timeout: Duration) {
+        let (Some(notifier), Some(seat)) = (&self.idle_notifier, &self.seat) else {
+            return;
+        };
+
+        if self.idle.notifications.iter().any(|(t, _)| *t == timeout) {
+            return;
+        }
+
+        let millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+        let notification = notifier.get_idle_notification(millis, seat, qh, WindowId::default());
+        self.idle.notifications.push((timeout, notification));
+    }
+
+    pub fn unwatch_idle(&mut self, timeout: Duration) {
+        let notifications = &mut self.idle.notifications;
+        if let Some(index) = notifications.iter().position(|(t, _)| *t == timeout) {
+            notifications.remove(index).1.destroy();
+        }
+    }
+
+    #[must_use]
+    pub fn idle(&self) -> &Idle {
+        &self.idle
+    }
+
+    pub fn idle_mut(&mut self) -> &mut Idle {
+        &mut self.idle
+    }
+}
+
+impl Dispatch<ZwpIdleInhibitManagerV1, WindowId> for WlClient {
+    fn event(
+        _: &mut Self,
+        _: &ZwpIdleInhibitManagerV1,
+        _: zwp_idle_inhibit_manager_v1::Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwpIdleInhibitorV1, WindowId> for WlClient {
+    fn event(
+        _: &mut Self,
+        _: &ZwpIdleInhibitorV1,
+        _: zwp_idle_inhibitor_v1::Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ExtIdleNotifierV1, WindowId> for WlClient {
+    fn event(
+        _: &mut Self,
+        _: &ExtIdleNotifierV1,
+        _: ext_idle_notifier_v1::Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ExtIdleNotificationV1, WindowId> for WlClient {
+    fn event(
+        client: &mut Self,
+        proxy: &ExtIdleNotificationV1,
+        event: ExtIdleNotificationV1Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let idle = &mut client.idle;
+        let Some(timeout) = idle
+            .notifications
+            .iter()
+            .find(|(_, n)| n == proxy)
+            .map(|(t, _)| *t)
+        else {
+            return;
+        };
+
+        match event {
+            ExtIdleNotificationV1Event::Idled => idle.events.push(IdleEvent::Idled(timeout)),
+            ExtIdleNotificationV1Event::Resumed => idle.events.push(IdleEvent::Resumed(timeout)),
+            _ => {}
+        }
+    }
+}
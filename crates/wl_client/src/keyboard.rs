@@ -0,0 +1,345 @@
+use std::time::{Duration, Instant};
+
+use wayland_client::{
+    protocol::wl_keyboard::{
+        Event as WlKeyboardEvent, KeyState as WlKeyState, KeymapFormat, WlKeyboard,
+    },
+    Connection, Dispatch, Proxy, QueueHandle, WEnum,
+};
+use xkbcommon::xkb;
+pub use xkbcommon::xkb::Keysym;
+
+use crate::{window::WindowId, WlClient};
+
+/// Offset between evdev scancodes sent by the compositor and xkb keycodes.
+const EVDEV_OFFSET: u32 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyState {
+    Pressed,
+    Released,
+    Repeated,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Modifiers {
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+    logo: bool,
+    caps_lock: bool,
+    num_lock: bool,
+}
+
+impl Modifiers {
+    #[must_use]
+    pub const fn ctrl(&self) -> bool {
+        self.ctrl
+    }
+
+    #[must_use]
+    pub const fn alt(&self) -> bool {
+        self.alt
+    }
+
+    #[must_use]
+    pub const fn shift(&self) -> bool {
+        self.shift
+    }
+
+    #[must_use]
+    pub const fn logo(&self) -> bool {
+        self.logo
+    }
+
+    #[must_use]
+    pub const fn caps_lock(&self) -> bool {
+        self.caps_lock
+    }
+
+    #[must_use]
+    pub const fn num_lock(&self) -> bool {
+        self.num_lock
+    }
+
+    fn from_state(state: &xkb::State) -> Self {
+        let active = |name| state.mod_name_is_active(name, xkb::STATE_MODS_EFFECTIVE);
+        Self {
+            ctrl: active(xkb::MOD_NAME_CTRL),
+            alt: active(xkb::MOD_NAME_ALT),
+            shift: active(xkb::MOD_NAME_SHIFT),
+            logo: active(xkb::MOD_NAME_LOGO),
+            caps_lock: active(xkb::MOD_NAME_CAPS),
+            num_lock: active(xkb::MOD_NAME_NUM),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyEvent {
+    keysym: Keysym,
+    code: u32,
+    state: KeyState,
+    text: Option<String>,
+    modifiers: Modifiers,
+}
+
+impl KeyEvent {
+    #[must_use]
+    pub const fn keysym(&self) -> Keysym {
+        self.keysym
+    }
+
+    /// Raw evdev scancode of the key.
+    #[must_use]
+    pub const fn code(&self) -> u32 {
+        self.code
+    }
+
+    #[must_use]
+    pub const fn state(&self) -> KeyState {
+        self.state
+    }
+
+    /// Text produced by the key, `None` for keys like arrows or function keys.
+    #[must_use]
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    #[must_use]
+    pub const fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    #[must_use]
+    pub const fn is_pressed(&self) -> bool {
+        matches!(self.state, KeyState::Pressed | KeyState::Repeated)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RepeatInfo {
+    /// Characters per second, `0` disables repeat.
+    rate: i32,
+    /// Delay in milliseconds before the first repeat.
+    delay: i32,
+}
+
+impl Default for RepeatInfo {
+    fn default() -> Self {
+        Self {
+            rate: 25,
+            delay: 600,
+        }
+    }
+}
+
+struct Repeat {
+    event: KeyEvent,
+    next: Instant,
+}
+
+struct Xkb {
+    keymap: xkb::Keymap,
+    state: xkb::State,
+}
+
+pub struct Keyboard {
+    context: xkb::Context,
+    xkb: Option<Xkb>,
+
+    focus: Option<WindowId>,
+    modifiers: Modifiers,
+    repeat_info: RepeatInfo,
+    repeat: Option<Repeat>,
+
+    events: Vec<KeyEvent>,
+}
+
+impl Default for Keyboard {
+    fn default() -> Self {
+        Self {
+            context: xkb::Context::new(xkb::CONTEXT_NO_FLAGS),
+            xkb: None,
+            focus: None,
+            modifiers: Modifiers::default(),
+            repeat_info: RepeatInfo::default(),
+            repeat: None,
+            events: vec![],
+        }
+    }
+}
+
+impl Keyboard {
+    /// Window which currently has keyboard focus.
+    #[must_use]
+    pub fn focus(&self) -> Option<&WindowId> {
+        self.focus.as_ref()
+    }
+
+    #[must_use]
+    pub const fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Returns the key events received since the last call, including synthesized repeats.
+    pub fn take_events(&mut self) -> Vec<KeyEvent> {
+        self.tick_repeat(Instant::now());
+        std::mem::take(&mut self.events)
+    }
+
+    /// When the held key repeats next, the event loop must wake up by then to deliver it.
+    #[must_use]
+    pub fn next_repeat(&self) -> Option<Instant> {
+        self.repeat
+            .as_ref()
+            .filter(|_| self.repeat_info.rate > 0)
+            .map(|repeat| repeat.next)
+    }
+
+    fn tick_repeat(&mut self, now: Instant) {
+        let Some(repeat) = self.repeat.as_mut() else {
+            return;
+        };
+
+        if self.repeat_info.rate <= 0 {
+            return;
+        }
+
+        let interval = Duration::from_secs(1) / self.repeat_info.rate as u32;
+        while repeat.next <= now {
+            self.events.push(repeat.event.clone());
+            repeat.next += interval;
+        }
+    }
+
+    fn key(&mut self, code: u32, state: KeyState) {
+        let Some(xkb) = self.xkb.as_ref() else {
+            return;
+        };
+
+        let keycode = xkb::Keycode::new(code + EVDEV_OFFSET);
+        if state == KeyState::Released {
+            if self.repeat.as_ref().is_some_and(|r| r.event.code == code) {
+                self.repeat = None;
+            }
+
+            self.events.push(KeyEvent {
+                keysym: xkb.state.key_get_one_sym(keycode),
+                code,
+                state: KeyState::Released,
+                text: None,
+                modifiers: self.modifiers,
+            });
+            return;
+        }
+
+        let text = xkb.state.key_get_utf8(keycode);
+        let text = (!text.is_empty() && !text.chars().any(char::is_control)).then_some(text);
+
+        let event = KeyEvent {
+            keysym: xkb.state.key_get_one_sym(keycode),
+            code,
+            state,
+            text,
+            modifiers: self.modifiers,
+        };
+
+        if state == KeyState::Pressed
+            && self.repeat_info.rate > 0
+            && xkb.keymap.key_repeats(keycode)
+        {
+            self.repeat = Some(Repeat {
+                event: KeyEvent {
+                    state: KeyState::Repeated,
+                    ..event.clone()
+                },
+                next: Instant::now() + Duration::from_millis(self.repeat_info.delay as u64),
+            });
+        }
+
+        self.events.push(event);
+    }
+}
+
+impl Dispatch<WlKeyboard, WindowId> for WlClient {
+    fn event(
+        client: &mut Self,
+        _: &WlKeyboard,
+        event: WlKeyboardEvent,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let WlKeyboardEvent::Key {
+            serial,
+            state: WEnum::Value(WlKeyState::Pressed),
+            ..
+        } = event
+        {
+            client.serial = serial;
+        }
+
+        let keyboard = &mut client.keyboard;
+        match event {
+            WlKeyboardEvent::Keymap { format, fd, size } => {
+                if format != WEnum::Value(KeymapFormat::XkbV1) {
+                    return;
+                }
+
+                // SAFETY: `fd` is owned and valid, it comes with the event. The compositor
+                // doesn't write to the keymap file once sent, and the mapping is dropped
+                // before the call returns.
+                let keymap = unsafe {
+                    xkb::Keymap::new_from_fd(
+                        &keyboard.context,
+                        fd,
+                        size as usize,
+                        xkb::KEYMAP_FORMAT_TEXT_V1,
+                        xkb::KEYMAP_COMPILE_NO_FLAGS,
+                    )
+                };
+
+                if let Ok(Some(keymap)) = keymap {
+                    let state = xkb::State::new(&keymap);
+                    keyboard.xkb = Some(Xkb { keymap, state });
+                }
+            }
+            WlKeyboardEvent::Enter { surface, .. } => {
+                keyboard.focus = surface.data::<WindowId>().cloned();
+            }
+            WlKeyboardEvent::Leave { .. } => {
+                keyboard.focus = None;
+                keyboard.repeat = None;
+            }
+            WlKeyboardEvent::Key { key, state, .. } => match state {
+                WEnum::Value(WlKeyState::Pressed) => keyboard.key(key, KeyState::Pressed),
+                WEnum::Value(WlKeyState::Released) => keyboard.key(key, KeyState::Released),
+                // From version 10, compositors that turned client repeat off repeat keys
+                WEnum::Value(WlKeyState::Repeated) => keyboard.key(key, KeyState::Repeated),
+                _ => {}
+            },
+            WlKeyboardEvent::Modifiers {
+                mods_depressed,
+                mods_latched,
+                mods_locked,
+                group,
+                ..
+            } => {
+                if let Some(xkb) = keyboard.xkb.as_mut() {
+                    xkb.state
+                        .update_mask(mods_depressed, mods_latched, mods_locked, 0, 0, group);
+                    keyboard.modifiers = Modifiers::from_state(&xkb.state);
+                }
+            }
+            WlKeyboardEvent::RepeatInfo { rate, delay } => {
+                keyboard.repeat_info = RepeatInfo { rate, delay };
+                if rate <= 0 {
+                    keyboard.repeat = None;
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -5,17 +5,31 @@
 #![allow(clippy::missing_panics_doc)]
 #![allow(clippy::missing_errors_doc)]
 
+pub mod clipboard;
+mod cursor;
+pub mod dnd;
+pub mod foreign_toplevel;
+pub mod idle;
+pub mod keyboard;
+pub mod output;
+mod pointer;
+mod scale;
+pub mod session_lock;
+pub mod text_input;
+pub mod touch;
 pub mod window;
-use glam::Vec2;
-pub use smithay_client_toolkit::reexports::protocols_wlr::layer_shell::v1::client::zwlr_layer_surface_v1::Anchor;
+pub mod workspace;
+pub use smithay_client_toolkit::reexports::protocols_wlr::layer_shell::v1::client::zwlr_layer_surface_v1::{Anchor, KeyboardInteractivity};
 pub use smithay_client_toolkit::reexports::protocols_wlr::layer_shell::v1::client::zwlr_layer_shell_v1::Layer;
+pub use wayland_protocols::xdg::shell::client::xdg_toplevel::ResizeEdge;
 
+use calloop::{channel, LoopHandle};
 use std::{
     collections::HashMap,
-    process::exit,
     sync::{Arc, Mutex},
 };
 
+use smithay_client_toolkit::reexports::protocols_wlr::foreign_toplevel::v1::client::zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1;
 use smithay_client_toolkit::reexports::protocols_wlr::layer_shell::v1::client::{
     zwlr_layer_shell_v1::{Event as ZwlrLayerShellV1Event, ZwlrLayerShellV1},
     zwlr_layer_surface_v1::{Event as ZwlrLayerSurfaceV1Event, ZwlrLayerSurfaceV1},
@@ -26,24 +40,74 @@ use wayland_client::{
         wl_buffer::{Event as WlBufferEvent, WlBuffer},
         wl_callback::{Event as WlCallbackEvent, WlCallback},
         wl_compositor::{Event as WlCompositorEvent, WlCompositor},
-        wl_output::{Event as WlOutputEvent, WlOutput},
-        wl_pointer::{Event as WlPointerEvent, WlPointer},
+        wl_data_device_manager::WlDataDeviceManager,
+        wl_keyboard::WlKeyboard,
+        wl_output::WlOutput,
+        wl_pointer::WlPointer,
         wl_registry::{Event as WlRegistryEvent, WlRegistry},
-        wl_seat::{Event as WlSeatEvent, WlSeat},
+        wl_seat::{Capability, Event as WlSeatEvent, WlSeat},
         wl_shm::{Event as WlShmEvent, WlShm},
         wl_shm_pool::{Event as WlShmPoolEvent, WlShmPool},
         wl_surface::{Event as WlSurfaceEvent, WlSurface},
+        wl_touch::WlTouch,
     },
-    Connection, Dispatch, Proxy, QueueHandle,
+    Connection, Dispatch, QueueHandle, WEnum,
 };
 
-use wayland_protocols::xdg::shell::client::{
-    xdg_surface::{Event as XdgSurfaceEvent, XdgSurface},
-    xdg_toplevel::{Event as XdgTopLevelEvent, XdgToplevel},
-    xdg_wm_base::{Event as XdgWmBaseEvent, XdgWmBase},
+use wayland_protocols::{
+    ext::{
+        foreign_toplevel_list::v1::client::ext_foreign_toplevel_list_v1::ExtForeignToplevelListV1,
+        idle_notify::v1::client::ext_idle_notifier_v1::ExtIdleNotifierV1,
+        session_lock::v1::client::ext_session_lock_manager_v1::ExtSessionLockManagerV1,
+        workspace::v1::client::ext_workspace_manager_v1::ExtWorkspaceManagerV1,
+    },
+    wp::{
+        cursor_shape::v1::client::wp_cursor_shape_manager_v1::WpCursorShapeManagerV1,
+        fractional_scale::v1::client::wp_fractional_scale_manager_v1::WpFractionalScaleManagerV1,
+        idle_inhibit::zv1::client::zwp_idle_inhibit_manager_v1::ZwpIdleInhibitManagerV1,
+        primary_selection::zv1::client::zwp_primary_selection_device_manager_v1::ZwpPrimarySelectionDeviceManagerV1,
+        text_input::zv3::client::zwp_text_input_manager_v3::ZwpTextInputManagerV3,
+        viewporter::client::wp_viewporter::WpViewporter,
+    },
+    xdg::{
+        decoration::zv1::client::{
+            zxdg_decoration_manager_v1::{
+                Event as ZxdgDecorationManagerV1Event, ZxdgDecorationManagerV1,
+            },
+            zxdg_toplevel_decoration_v1::{
+                Event as ZxdgToplevelDecorationV1Event, Mode as DecorationMode,
+                ZxdgToplevelDecorationV1,
+            },
+        },
+        shell::client::{
+            xdg_popup::{Event as XdgPopupEvent, XdgPopup},
+            xdg_positioner::{Event as XdgPositionerEvent, XdgPositioner},
+            xdg_surface::{Event as XdgSurfaceEvent, XdgSurface},
+            xdg_toplevel::{Event as XdgTopLevelEvent, XdgToplevel},
+            xdg_wm_base::{Event as XdgWmBaseEvent, XdgWmBase},
+        },
+    },
 };
 
-use crate::window::{ShmPool, Window, WindowId, WindowLayer};
+use crate::{
+    clipboard::Clipboard,
+    cursor::Cursor,
+    dnd::DragAndDrop,
+    foreign_toplevel::ForeignToplevels,
+    idle::Idle,
+    keyboard::Keyboard,
+    output::Outputs,
+    session_lock::SessionLock,
+    text_input::TextInput,
+    touch::Touch,
+    window::{
+        PopupContext, ShmPool, TargetMonitor, Window, WindowEvent, WindowId, WindowLayer,
+        WindowState,
+    },
+    workspace::Workspaces,
+};
+pub use cursor::CursorShape;
+pub use pointer::{ButtonState, Pointer, Scroll, ScrollSource};
 
 const DESKTOP_DEFAULT_WIDTH: i32 = 600;
 const DESKTOP_DEFAULT_HEIGHT: i32 = 400;
@@ -54,30 +118,89 @@ pub struct WlClient {
     xdg_wm_base: Option<XdgWmBase>,
     layer_shell: Option<ZwlrLayerShellV1>,
     shm: Option<WlShm>,
-
-    outputs: HashMap<String, WlOutput>,
+    decoration_manager: Option<ZxdgDecorationManagerV1>,
+    fractional_scale_manager: Option<WpFractionalScaleManagerV1>,
+    viewporter: Option<WpViewporter>,
+    cursor_shape_manager: Option<WpCursorShapeManagerV1>,
+    data_device_manager: Option<WlDataDeviceManager>,
+    primary_selection_manager: Option<ZwpPrimarySelectionDeviceManagerV1>,
+    text_input_manager: Option<ZwpTextInputManagerV3>,
+    foreign_toplevel_manager: Option<ZwlrForeignToplevelManagerV1>,
+    foreign_toplevel_list: Option<ExtForeignToplevelListV1>,
+    workspace_manager: Option<ExtWorkspaceManagerV1>,
+    session_lock_manager: Option<ExtSessionLockManagerV1>,
+    idle_inhibit_manager: Option<ZwpIdleInhibitManagerV1>,
+    idle_notifier: Option<ExtIdleNotifierV1>,
+
+    outputs: Outputs,
+    foreign_toplevels: ForeignToplevels,
+    workspaces: Workspaces,
     windows: HashMap<String, WindowBackend>,
+    window_events: Vec<WindowEvent>,
 
     seat: Option<WlSeat>,
+    /// Serial of the latest button, key or touch press, required to grab the seat.
+    serial: u32,
+    wl_pointer: Option<WlPointer>,
+    wl_keyboard: Option<WlKeyboard>,
+    wl_touch: Option<WlTouch>,
+    cursor: Option<Cursor>,
+    clipboard: Clipboard,
+    dnd: DragAndDrop,
+    text_input: TextInput,
+    session_lock: SessionLock,
+    idle: Idle,
     pointer: Pointer,
+    keyboard: Keyboard,
+    touch: Touch,
 }
 
 impl WlClient {
+    /// Watches the reads running on background threads on the event loop of `handle`, so that
+    /// their results wake it up.
+    ///
+    /// Must be called once, the results are otherwise never delivered.
+    pub fn insert_sources(
+        &mut self,
+        handle: &LoopHandle<'static, WlClient>,
+    ) -> Result<(), calloop::Error> {
+        if let Some(channel) = self.clipboard.take_channel() {
+            handle
+                .insert_source(channel, |event, (), client| {
+                    if let channel::Event::Msg(event) = event {
+                        client.clipboard.push_event(event);
+                    }
+                })
+                .map_err(|e| e.error)?;
+        }
+
+        if let Some(channel) = self.dnd.take_channel() {
+            handle
+                .insert_source(channel, |event, (), client| {
+                    if let channel::Event::Msg((drop, offer)) = event {
+                        client.dnd.finish_drop(drop, offer);
+                    }
+                })
+                .map_err(|e| e.error)?;
+        }
+
+        Ok(())
+    }
+
     fn create_surface(
         &mut self,
         qh: &QueueHandle<WlClient>,
         id: &Arc<String>,
         width: i32,
         height: i32,
-    ) -> (WlSurface, ShmPool, WlBuffer) {
+    ) -> (WlSurface, ShmPool) {
         let compositor = self.compositor.as_ref().expect("unreachable");
         let shm = self.shm.as_ref().expect("unreachable");
 
         let surface = compositor.create_surface(qh, id.clone());
         let pool = ShmPool::new((width as u64 * 4) * height as u64, id, shm, qh);
-        let buffer = pool.create_buffer(0, width, height, qh, id);
 
-        (surface, pool, buffer)
+        (surface, pool)
     }
 
     pub fn create_window_backend(
@@ -87,32 +210,78 @@ impl WlClient {
         width: u32,
         height: u32,
         layer: WindowLayer,
+        output: Option<&str>,
     ) -> Arc<Mutex<Window>> {
         let width = width as i32;
         let height = height as i32;
+        let output = output.and_then(|name| self.outputs.proxy(name).cloned());
+
+        let popup = layer.parent().map(|parent| PopupContext {
+            parent: self.windows[parent]
+                .lock()
+                .unwrap()
+                .popup_parent()
+                .expect("parent window has a role"),
+            grab: match &layer {
+                WindowLayer::Popup(options) if options.grab => {
+                    self.grab().map(|(seat, serial)| (seat.clone(), serial))
+                }
+                _ => None,
+            },
+        });
+
+        let lock = match (&layer, &self.session_lock_manager) {
+            (WindowLayer::Lock, Some(manager)) => {
+                Some(self.session_lock.get_or_lock(&qh, manager).clone())
+            }
+            (WindowLayer::Lock, None) => {
+                self.session_lock.unsupported();
+                None
+            }
+            _ => None,
+        };
 
         let id = id.into();
         let arc_id = Arc::new(id.clone());
-        let (surface, pool, buffer) = self.create_surface(&qh, &arc_id, width, height);
+        // Layer windows with a zero size wait for the compositor to configure it
+        let (surface, pool) = self.create_surface(&qh, &arc_id, width.max(1), height.max(1));
 
         let window = Arc::new(Mutex::new(Window::new(
             Some(self.layer_shell.as_ref().expect("unreachable")),
             Some(self.xdg_wm_base.as_ref().expect("unreachable")),
+            output.as_ref(),
+            popup,
+            lock.as_ref(),
+            self.decoration_manager.as_ref(),
             qh,
             arc_id,
             surface,
             pool,
-            buffer,
             width,
             height,
             layer,
         )));
 
+        if let (Some(manager), Some(viewporter)) =
+            (&self.fractional_scale_manager, &self.viewporter)
+        {
+            window
+                .lock()
+                .unwrap()
+                .init_fractional_scale(manager, viewporter);
+        }
+
         self.windows.insert(id, window.clone());
         window
     }
 
+    #[must_use]
+    pub fn window(&self, window_id: &str) -> Option<&WindowBackend> {
+        self.windows.get(window_id)
+    }
+
     pub fn destroy_window_backend(&mut self, window_id: &str) {
+        self.idle.remove_inhibitor(window_id);
         let window = self.windows.remove(window_id).unwrap();
         let window = Arc::try_unwrap(window)
             .expect("Arc has other references")
@@ -122,10 +291,84 @@ impl WlClient {
         window.destroy();
     }
 
+    /// Resolves `target` to the names of the outputs a layer surface should be created on.
+    ///
+    /// `None` lets the compositor choose. An empty list means the requested output is not
+    /// connected.
+    #[must_use]
+    pub fn target_outputs(&self, target: &TargetMonitor) -> Vec<Option<String>> {
+        match target {
+            TargetMonitor::Primary => vec![None],
+            TargetMonitor::Name(name) => self
+                .outputs
+                .get(name)
+                .map(|o| Some(o.name().to_string()))
+                .into_iter()
+                .collect(),
+            TargetMonitor::Index(index) => self
+                .outputs
+                .iter()
+                .nth(*index)
+                .map(|o| Some(o.name().to_string()))
+                .into_iter()
+                .collect(),
+            TargetMonitor::All => self
+                .outputs
+                .iter()
+                .map(|o| Some(o.name().to_string()))
+                .collect(),
+        }
+    }
+
+    /// Returns the close requests and closures received since the previous call.
+    pub fn take_window_events(&mut self) -> Vec<WindowEvent> {
+        std::mem::take(&mut self.window_events)
+    }
+
+    /// Seat and serial of the latest press, which interactive moves, resizes, window menus
+    /// and popup grabs must be tied to.
+    #[must_use]
+    pub fn grab(&self) -> Option<(&WlSeat, u32)> {
+        self.seat.as_ref().map(|seat| (seat, self.serial))
+    }
+
+    /// Sets the image of the pointer until it leaves the window it is over.
+    pub fn set_cursor(&mut self, shape: CursorShape) {
+        if let (Some(pointer), Some(cursor)) = (&self.wl_pointer, &mut self.cursor) {
+            cursor.set(pointer, shape);
+        }
+    }
+
+    #[must_use]
+    pub fn outputs(&self) -> &Outputs {
+        &self.outputs
+    }
+
+    pub fn outputs_mut(&mut self) -> &mut Outputs {
+        &mut self.outputs
+    }
+
     #[must_use]
     pub fn pointer(&self) -> &Pointer {
         &self.pointer
     }
+
+    pub fn pointer_mut(&mut self) -> &mut Pointer {
+        &mut self.pointer
+    }
+
+    #[must_use]
+    pub fn keyboard(&self) -> &Keyboard {
+        &self.keyboard
+    }
+
+    pub fn keyboard_mut(&mut self) -> &mut Keyboard {
+        &mut self.keyboard
+    }
+
+    pub fn touch_mut(&mut self) -> &mut Touch {
+        &mut self.touch
+    }
 }
 
 impl Dispatch<WlRegistry, WindowId> for WlClient {
@@ -137,6 +380,11 @@ impl Dispatch<WlRegistry, WindowId> for WlClient {
         _: &Connection,
         qh: &QueueHandle<WlClient>,
     ) {
+        if let WlRegistryEvent::GlobalRemove { name } = event {
+            state.outputs.remove(name);
+            return;
+        }
+
         if let WlRegistryEvent::Global {
             name,
             interface,
@@ -168,9 +416,123 @@ impl Dispatch<WlRegistry, WindowId> for WlClient {
                         id.clone(),
                     ));
                 }
+                "zxdg_decoration_manager_v1" => {
+                    state.decoration_manager =
+                        Some(registry.bind::<ZxdgDecorationManagerV1, _, _>(
+                            name,
+                            version,
+                            qh,
+                            id.clone(),
+                        ));
+                }
+                "wp_fractional_scale_manager_v1" => {
+                    state.fractional_scale_manager =
+                        Some(registry.bind::<WpFractionalScaleManagerV1, _, _>(
+                            name,
+                            version,
+                            qh,
+                            id.clone(),
+                        ));
+                }
+                "wp_cursor_shape_manager_v1" => {
+                    state.cursor_shape_manager =
+                        Some(registry.bind::<WpCursorShapeManagerV1, _, _>(
+                            name,
+                            version,
+                            qh,
+                            id.clone(),
+                        ));
+                }
+                "wl_data_device_manager" => {
+                    state.data_device_manager = Some(registry.bind::<WlDataDeviceManager, _, _>(
+                        name,
+                        version,
+                        qh,
+                        id.clone(),
+                    ));
+                }
+                "zwp_primary_selection_device_manager_v1" => {
+                    state.primary_selection_manager =
+                        Some(registry.bind::<ZwpPrimarySelectionDeviceManagerV1, _, _>(
+                            name,
+                            version,
+                            qh,
+                            id.clone(),
+                        ));
+                }
+                "zwp_text_input_manager_v3" => {
+                    state.text_input_manager = Some(registry.bind::<ZwpTextInputManagerV3, _, _>(
+                        name,
+                        version,
+                        qh,
+                        id.clone(),
+                    ));
+                }
+                "zwlr_foreign_toplevel_manager_v1" => {
+                    state.foreign_toplevel_manager =
+                        Some(registry.bind::<ZwlrForeignToplevelManagerV1, _, _>(
+                            name,
+                            version,
+                            qh,
+                            id.clone(),
+                        ));
+
+                    // Also lists the toplevels, with their state and controls
+                    if let Some(list) = &state.foreign_toplevel_list {
+                        list.stop();
+                        state.foreign_toplevels.remove_listed();
+                    }
+                }
+                "ext_foreign_toplevel_list_v1" if state.foreign_toplevel_manager.is_none() => {
+                    state.foreign_toplevel_list =
+                        Some(registry.bind::<ExtForeignToplevelListV1, _, _>(
+                            name,
+                            version,
+                            qh,
+                            id.clone(),
+                        ));
+                }
+                "ext_workspace_manager_v1" => {
+                    state.workspace_manager = Some(registry.bind::<ExtWorkspaceManagerV1, _, _>(
+                        name,
+                        version,
+                        qh,
+                        id.clone(),
+                    ));
+                }
+                "ext_session_lock_manager_v1" => {
+                    state.session_lock_manager =
+                        Some(registry.bind::<ExtSessionLockManagerV1, _, _>(
+                            name,
+                            version,
+                            qh,
+                            id.clone(),
+                        ));
+                }
+                "zwp_idle_inhibit_manager_v1" => {
+                    state.idle_inhibit_manager =
+                        Some(registry.bind::<ZwpIdleInhibitManagerV1, _, _>(
+                            name,
+                            version,
+                            qh,
+                            id.clone(),
+                        ));
+                }
+                "ext_idle_notifier_v1" => {
+                    state.idle_notifier = Some(registry.bind::<ExtIdleNotifierV1, _, _>(
+                        name,
+                        version,
+                        qh,
+                        id.clone(),
+                    ));
+                }
+                "wp_viewporter" => {
+                    state.viewporter =
+                        Some(registry.bind::<WpViewporter, _, _>(name, version, qh, id.clone()));
+                }
                 "wl_output" => {
                     let output = registry.bind::<WlOutput, _, _>(name, version, qh, id.clone());
-                    state.outputs.insert(output.id().to_string(), output);
+                    state.outputs.insert(name, output);
                 }
                 _ => {}
             }
@@ -178,45 +540,6 @@ impl Dispatch<WlRegistry, WindowId> for WlClient {
     }
 }
 
-#[allow(unused)]
-impl Dispatch<WlOutput, WindowId> for WlClient {
-    fn event(
-        state: &mut Self,
-        output: &WlOutput,
-        event: WlOutputEvent,
-        _: &WindowId,
-        _: &Connection,
-        _: &QueueHandle<Self>,
-    ) {
-        match event {
-            WlOutputEvent::Geometry {
-                x,
-                y,
-                physical_width,
-                physical_height,
-                subpixel,
-                make,
-                model,
-                transform,
-            } => {}
-            WlOutputEvent::Mode {
-                flags,
-                width,
-                height,
-                refresh,
-            } => {}
-            WlOutputEvent::Scale { factor } => {}
-            WlOutputEvent::Name { name } => {
-                let id = output.id().to_string();
-                let output = state.outputs.remove(&id).unwrap();
-                state.outputs.insert(name, output);
-            }
-            WlOutputEvent::Description { description } => {}
-            WlOutputEvent::Done | _ => {}
-        }
-    }
-}
-
 impl Dispatch<WlCompositor, WindowId> for WlClient {
     fn event(
         _: &mut Self,
@@ -239,17 +562,20 @@ impl Dispatch<WlSurface, WindowId> for WlClient {
         _: &QueueHandle<Self>,
     ) {
         match event {
-            WlSurfaceEvent::Enter { output: _ } => println!("Enter"),
-            WlSurfaceEvent::Leave { output: _ } => println!("Leave"),
+            // Scale comes from `preferred_buffer_scale` instead of the outputs entered
+            WlSurfaceEvent::Enter { output: _ } | WlSurfaceEvent::Leave { output: _ } => {}
 
+            // The cursor surface has no window
             WlSurfaceEvent::PreferredBufferScale { factor } => {
-                let mut window = state.windows.get_mut(id.as_str()).unwrap().lock().unwrap();
-                window.scale = factor;
+                if let Some(window) = state.windows.get(id.as_str()) {
+                    window.lock().unwrap().set_scale(factor);
+                }
             }
 
             WlSurfaceEvent::PreferredBufferTransform { transform } => {
-                let mut window = state.windows.get_mut(id.as_str()).unwrap().lock().unwrap();
-                window.transform = transform.into();
+                if let Some(window) = state.windows.get(id.as_str()) {
+                    window.lock().unwrap().transform = transform.into();
+                }
             }
 
             _ => {}
@@ -283,13 +609,18 @@ impl Dispatch<WlShm, WindowId> for WlClient {
 
 impl Dispatch<WlBuffer, WindowId> for WlClient {
     fn event(
-        _: &mut Self,
-        _: &WlBuffer,
-        _: WlBufferEvent,
-        _: &WindowId,
+        state: &mut Self,
+        buffer: &WlBuffer,
+        event: WlBufferEvent,
+        id: &WindowId,
         _: &Connection,
         _: &QueueHandle<Self>,
     ) {
+        if let WlBufferEvent::Release = event
+            && let Some(window) = state.windows.get(id.as_str())
+        {
+            window.lock().unwrap().release_buffer(buffer);
+        }
     }
 }
 
@@ -323,6 +654,7 @@ impl Dispatch<XdgSurface, WindowId> for WlClient {
             window.resize_pool_if_needed();
             window.resize_buffer_if_needed();
             window.commit();
+            window.configured = true;
         }
     }
 }
@@ -340,9 +672,19 @@ impl Dispatch<XdgToplevel, WindowId> for WlClient {
             XdgTopLevelEvent::Configure {
                 mut width,
                 mut height,
-                states: _, //TODO
+                states,
             } => {
                 let mut window = state.windows.get_mut(id.as_str()).unwrap().lock().unwrap();
+
+                let window_state = WindowState::from_configure(&states)
+                    | (window.state & WindowState::ServerDecorations);
+                if window.state != window_state {
+                    window.state = window_state;
+                    state
+                        .window_events
+                        .push(WindowEvent::StateChanged(id.clone(), window_state));
+                }
+
                 if let WindowLayer::Desktop(opts) = &window.layer {
                     if !opts.resizable {
                         return;
@@ -361,7 +703,11 @@ impl Dispatch<XdgToplevel, WindowId> for WlClient {
 
                 window.can_resize = true;
             }
-            XdgTopLevelEvent::Close => exit(0), //TODO
+            XdgTopLevelEvent::Close => {
+                state
+                    .window_events
+                    .push(WindowEvent::CloseRequested(id.clone()));
+            }
             //XdgTopLevelEvent::ConfigureBounds { width, height } => todo!(),
             //XdgTopLevelEvent::WmCapabilities { capabilities } => todo!(),
             _ => {}
@@ -369,6 +715,80 @@ impl Dispatch<XdgToplevel, WindowId> for WlClient {
     }
 }
 
+impl Dispatch<ZxdgToplevelDecorationV1, WindowId> for WlClient {
+    fn event(
+        state: &mut Self,
+        _: &ZxdgToplevelDecorationV1,
+        event: ZxdgToplevelDecorationV1Event,
+        id: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let ZxdgToplevelDecorationV1Event::Configure { mode } = event {
+            let mut window = state.windows.get_mut(id.as_str()).unwrap().lock().unwrap();
+            let server_side = mode == WEnum::Value(DecorationMode::ServerSide);
+            if window.state.contains(WindowState::ServerDecorations) != server_side {
+                window
+                    .state
+                    .set(WindowState::ServerDecorations, server_side);
+                state
+                    .window_events
+                    .push(WindowEvent::StateChanged(id.clone(), window.state));
+            }
+        }
+    }
+}
+
+impl Dispatch<ZxdgDecorationManagerV1, WindowId> for WlClient {
+    fn event(
+        _: &mut Self,
+        _: &ZxdgDecorationManagerV1,
+        _: ZxdgDecorationManagerV1Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<XdgPopup, WindowId> for WlClient {
+    fn event(
+        state: &mut Self,
+        _: &XdgPopup,
+        event: XdgPopupEvent,
+        id: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            XdgPopupEvent::Configure { width, height, .. } => {
+                let mut window = state.windows.get_mut(id.as_str()).unwrap().lock().unwrap();
+                if width > 0 && height > 0 && (width, height) != (window.width, window.height) {
+                    window.width = width;
+                    window.height = height;
+                    window.can_resize = true;
+                }
+            }
+            XdgPopupEvent::PopupDone => {
+                state.window_events.push(WindowEvent::PopupDone(id.clone()));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<XdgPositioner, WindowId> for WlClient {
+    fn event(
+        _: &mut Self,
+        _: &XdgPositioner,
+        _: XdgPositionerEvent,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
 impl Dispatch<ZwlrLayerShellV1, WindowId> for WlClient {
     fn event(
         _: &mut Self,
@@ -393,17 +813,27 @@ impl Dispatch<ZwlrLayerSurfaceV1, WindowId> for WlClient {
         match event {
             ZwlrLayerSurfaceV1Event::Configure {
                 serial,
-                width: _,  //TODO
-                height: _, //TODO
+                width,
+                height,
             } => {
                 surface.ack_configure(serial);
                 let mut window = state.windows.get_mut(id.as_str()).unwrap().lock().unwrap();
-                window.resize_buffer_if_needed();
-                window.draw();
+
+                // Zero leaves the dimension to the client
+                if width != 0 && width as i32 != window.width {
+                    window.width = width as i32;
+                    window.can_resize = true;
+                }
+
+                if height != 0 && height as i32 != window.height {
+                    window.height = height as i32;
+                    window.can_resize = true;
+                }
+
+                window.configure();
             }
             ZwlrLayerSurfaceV1Event::Closed => {
-                println!("Layer surface event 'closed'");
-                //TODO
+                state.window_events.push(WindowEvent::Closed(id.clone()));
             }
             _ => {}
         }
@@ -429,122 +859,75 @@ impl Dispatch<WlCallback, WindowId> for WlClient {
 
 impl Dispatch<WlSeat, WindowId> for WlClient {
     fn event(
-        _: &mut Self,
+        state: &mut Self,
         proxy: &WlSeat,
-        _: WlSeatEvent,
+        event: WlSeatEvent,
         data: &WindowId,
-        _: &Connection,
+        conn: &Connection,
         qh: &QueueHandle<Self>,
     ) {
-        proxy.get_pointer(qh, data.clone());
-    }
-}
+        let WlSeatEvent::Capabilities {
+            capabilities: WEnum::Value(capabilities),
+        } = event
+        else {
+            return;
+        };
 
-impl Dispatch<WlPointer, WindowId> for WlClient {
-    fn event(
-        client: &mut Self,
-        _: &WlPointer,
-        event: WlPointerEvent,
-        _: &WindowId,
-        _: &Connection,
-        _: &QueueHandle<Self>,
-    ) {
-        match event {
-            WlPointerEvent::Motion {
-                time: _,
-                surface_x,
-                surface_y,
-            }
-            | WlPointerEvent::Enter {
-                serial: _,
-                surface: _,
-                surface_x,
-                surface_y,
-            } => {
-                client.pointer.position = Vec2::new(surface_x as f32, surface_y as f32);
-            }
-            WlPointerEvent::Button {
-                serial: _,
-                time: _,
-                button,
-                state,
-            } => {
-                let state = match state {
-                    wayland_client::WEnum::Value(state) => matches!(
-                        state,
-                        wayland_client::protocol::wl_pointer::ButtonState::Pressed
-                    ),
-                    wayland_client::WEnum::Unknown(_) => unreachable!(),
-                };
-
-                match button {
-                    272 => client.pointer.buttons.left = state,
-                    273 => client.pointer.buttons.right = state,
-                    274 => client.pointer.buttons.middle = state,
-                    _ => {}
+        // Selections belong to the seat whatever input devices it has
+        state.clipboard.init_devices(
+            qh,
+            proxy,
+            state.data_device_manager.as_ref(),
+            state.primary_selection_manager.as_ref(),
+        );
+
+        if capabilities.contains(Capability::Pointer) {
+            if state.wl_pointer.is_none() {
+                let pointer = proxy.get_pointer(qh, data.clone());
+                if let (Some(compositor), Some(shm)) = (&state.compositor, &state.shm) {
+                    state.cursor = Some(Cursor::new(
+                        conn,
+                        qh,
+                        &pointer,
+                        state.cursor_shape_manager.as_ref(),
+                        compositor,
+                        shm,
+                    ));
                 }
-            }
-            //WlPointerEvent::Leave { serial: _, surface: _ } => {},
-            //WlPointerEvent::Axis { time, axis, value } => todo!(),
-            //WlPointerEvent::Frame => todo!(),
-            //WlPointerEvent::AxisSource { axis_source } => todo!(),
-            //WlPointerEvent::AxisStop { time, axis } => todo!(),
-            //WlPointerEvent::AxisDiscrete { axis, discrete } => todo!(),
-            //WlPointerEvent::AxisValue120 { axis, value120 } => todo!(),
-            //WlPointerEvent::AxisRelativeDirection { axis, direction } => todo!(),
-            _ => {}
-        }
-    }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Pointer {
-    position: Vec2,
-    buttons: ButtonState,
-}
-
-impl Pointer {
-    #[must_use]
-    pub const fn position(&self) -> Vec2 {
-        self.position
-    }
-
-    #[must_use]
-    pub const fn buttons(&self) -> ButtonState {
-        self.buttons
-    }
-}
+                state.wl_pointer = Some(pointer);
+            }
+        } else if let Some(pointer) = state.wl_pointer.take() {
+            if let Some(cursor) = state.cursor.take() {
+                cursor.destroy();
+            }
 
-impl Default for Pointer {
-    fn default() -> Self {
-        Self {
-            position: Vec2::MAX,
-            buttons: ButtonState::default(),
+            pointer.release();
         }
-    }
-}
-
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
-pub struct ButtonState {
-    left: bool,
-    right: bool,
-    middle: bool,
-}
 
-impl ButtonState {
-    #[must_use]
-    pub const fn left(&self) -> bool {
-        self.left
-    }
+        if capabilities.contains(Capability::Keyboard) {
+            if state.wl_keyboard.is_none() {
+                state.wl_keyboard = Some(proxy.get_keyboard(qh, data.clone()));
+            }
 
-    #[must_use]
-    pub const fn right(&self) -> bool {
-        self.right
-    }
+            // Input methods send text to the surface with the keyboard focus
+            state
+                .text_input
+                .init(qh, proxy, state.text_input_manager.as_ref());
+        } else if let Some(keyboard) = state.wl_keyboard.take() {
+            keyboard.release();
+            state.text_input.destroy();
+            state.keyboard = Keyboard::default();
+        }
 
-    #[must_use]
-    pub const fn middle(&self) -> bool {
-        self.middle
+        if capabilities.contains(Capability::Touch) {
+            if state.wl_touch.is_none() {
+                state.wl_touch = Some(proxy.get_touch(qh, data.clone()));
+            }
+        } else if let Some(touch) = state.wl_touch.take() {
+            touch.release();
+            state.touch = Touch::default();
+        }
     }
 }
 
@@ -0,0 +1,275 @@
+use glam::IVec2;
+use wayland_client::{
+    protocol::wl_output::{Event as WlOutputEvent, Mode, WlOutput},
+    Connection, Dispatch, Proxy, QueueHandle, WEnum,
+};
+
+use crate::{
+    window::{Transform, WindowId},
+    WlClient,
+};
+
+/// `wl_output.done` was introduced in version 2.
+const DONE_SINCE: u32 = 2;
+/// `wl_output.release` was introduced in version 3.
+const RELEASE_SINCE: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output {
+    name: String,
+    description: String,
+    make: String,
+    model: String,
+    position: IVec2,
+    physical_size: IVec2,
+    size: IVec2,
+    refresh: i32,
+    scale: i32,
+    transform: Transform,
+}
+
+impl Output {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            description: String::new(),
+            make: String::new(),
+            model: String::new(),
+            position: IVec2::ZERO,
+            physical_size: IVec2::ZERO,
+            size: IVec2::ZERO,
+            refresh: 0,
+            scale: 1,
+            transform: Transform::Normal0,
+        }
+    }
+
+    /// Connector name like `DP-1`, used by [`TargetMonitor::Name`](crate::window::TargetMonitor::Name).
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    #[must_use]
+    pub fn make(&self) -> &str {
+        &self.make
+    }
+
+    #[must_use]
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Position in the compositor global space.
+    #[must_use]
+    pub const fn position(&self) -> IVec2 {
+        self.position
+    }
+
+    /// Physical size in millimeters, zero when unknown.
+    #[must_use]
+    pub const fn physical_size(&self) -> IVec2 {
+        self.physical_size
+    }
+
+    /// Size of the current mode in pixels.
+    #[must_use]
+    pub const fn size(&self) -> IVec2 {
+        self.size
+    }
+
+    /// Size in surface coordinates, with the scale and rotation applied.
+    #[must_use]
+    pub fn logical_size(&self) -> IVec2 {
+        let size = self.size / self.scale.max(1);
+        if self.transform.is_rotated() {
+            IVec2::new(size.y, size.x)
+        } else {
+            size
+        }
+    }
+
+    /// Refresh rate of the current mode in hertz, zero when unknown.
+    #[must_use]
+    pub fn refresh_rate(&self) -> f32 {
+        self.refresh as f32 / 1000.0
+    }
+
+    #[must_use]
+    pub const fn scale(&self) -> i32 {
+        self.scale
+    }
+
+    #[must_use]
+    pub const fn transform(&self) -> Transform {
+        self.transform
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputEvent {
+    Added(Output),
+    Changed(Output),
+    Removed(Output),
+}
+
+#[derive(Debug)]
+struct OutputEntry {
+    /// Registry name of the `wl_output` global.
+    global: u32,
+    proxy: WlOutput,
+    info: Output,
+    /// State being received until the next `wl_output.done`, or applied right away before
+    /// version 2.
+    pending: Output,
+    /// The first `wl_output.done` was received and [`OutputEvent::Added`] raised.
+    announced: bool,
+}
+
+#[derive(Default, Debug)]
+pub struct Outputs {
+    entries: Vec<OutputEntry>,
+    events: Vec<OutputEvent>,
+}
+
+impl Outputs {
+    /// Announced outputs in the order the compositor advertised them.
+    pub fn iter(&self) -> impl Iterator<Item = &Output> {
+        self.entries.iter().filter(|e| e.announced).map(|e| &e.info)
+    }
+
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Output> {
+        self.iter().find(|o| o.name == name)
+    }
+
+    /// Returns the outputs added, changed or removed since the previous call.
+    pub fn take_events(&mut self) -> Vec<OutputEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub(crate) fn proxy(&self, name: &str) -> Option<&WlOutput> {
+        self.entries
+            .iter()
+            .find(|e| e.announced && e.info.name == name)
+            .map(|e| &e.proxy)
+    }
+
+    pub(crate) fn name_of(&self, proxy: &WlOutput) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|e| e.announced && e.proxy == *proxy)
+            .map(|e| e.info.name.as_str())
+    }
+
+    pub(crate) fn insert(&mut self, global: u32, proxy: WlOutput) {
+        let output = Output::new(proxy.id().to_string());
+        self.entries.push(OutputEntry {
+            global,
+            proxy,
+            info: output.clone(),
+            pending: output,
+            announced: false,
+        });
+    }
+
+    pub(crate) fn remove(&mut self, global: u32) {
+        let Some(index) = self.entries.iter().position(|e| e.global == global) else {
+            return;
+        };
+
+        let entry = self.entries.remove(index);
+        if entry.proxy.version() >= RELEASE_SINCE {
+            entry.proxy.release();
+        }
+
+        if entry.announced {
+            self.events.push(OutputEvent::Removed(entry.info));
+        }
+    }
+
+    fn commit(&mut self, proxy: &WlOutput) {
+        let Some(entry) = self.entries.iter_mut().find(|e| &e.proxy == proxy) else {
+            return;
+        };
+
+        if !entry.announced {
+            entry.announced = true;
+            entry.info = entry.pending.clone();
+            self.events.push(OutputEvent::Added(entry.info.clone()));
+        } else if entry.info != entry.pending {
+            entry.info = entry.pending.clone();
+            self.events.push(OutputEvent::Changed(entry.info.clone()));
+        }
+    }
+}
+
+impl Dispatch<WlOutput, WindowId> for WlClient {
+    fn event(
+        client: &mut Self,
+        proxy: &WlOutput,
+        event: WlOutputEvent,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let outputs = &mut client.outputs;
+        if let WlOutputEvent::Done = event {
+            outputs.commit(proxy);
+            return;
+        }
+
+        let Some(entry) = outputs.entries.iter_mut().find(|e| &e.proxy == proxy) else {
+            return;
+        };
+
+        let pending = &mut entry.pending;
+        match event {
+            WlOutputEvent::Geometry {
+                x,
+                y,
+                physical_width,
+                physical_height,
+                subpixel: _,
+                make,
+                model,
+                transform,
+            } => {
+                pending.position = IVec2::new(x, y);
+                pending.physical_size = IVec2::new(physical_width, physical_height);
+                pending.make = make;
+                pending.model = model;
+                pending.transform = transform.into();
+            }
+            WlOutputEvent::Mode {
+                flags,
+                width,
+                height,
+                refresh,
+            } => {
+                if let WEnum::Value(flags) = flags
+                    && flags.contains(Mode::Current)
+                {
+                    pending.size = IVec2::new(width, height);
+                    pending.refresh = refresh;
+                }
+            }
+            WlOutputEvent::Scale { factor } => pending.scale = factor,
+            WlOutputEvent::Name { name } => pending.name = name,
+            WlOutputEvent::Description { description } => pending.description = description,
+            _ => {}
+        }
+
+        // Without `wl_output.done` the output is complete once its current mode arrives,
+        // which follows the geometry
+        let complete = entry.announced || entry.pending.size != IVec2::ZERO;
+        if proxy.version() < DONE_SINCE && complete {
+            outputs.commit(proxy);
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use glam::{IVec2, Vec2};
+use wayland_client::{
+    protocol::wl_pointer::{
+        Axis, AxisSource, ButtonState as WlButtonState, Event as WlPointerEvent, WlPointer,
+    },
+    Connection, Dispatch, Proxy, QueueHandle, WEnum,
+};
+
+use crate::{window::WindowId, WlClient};
+
+/// `wl_pointer.frame` was introduced in version 5, older seats commit every event immediately.
+const FRAME_SINCE: u32 = 5;
+
+/// One wheel detent in `axis_value120` units.
+const VALUE120_STEP: i32 = 120;
+
+impl Dispatch<WlPointer, WindowId> for WlClient {
+    fn event(
+        client: &mut Self,
+        proxy: &WlPointer,
+        event: WlPointerEvent,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let WlPointerEvent::Button {
+            serial,
+            state: WEnum::Value(WlButtonState::Pressed),
+            ..
+        } = event
+        {
+            client.serial = serial;
+        }
+
+        if let WlPointerEvent::Enter {
+            serial, surface, ..
+        } = &event
+        {
+            let scale = surface
+                .data::<WindowId>()
+                .and_then(|id| client.windows.get(id.as_str()))
+                .map_or(1, |window| {
+                    window.lock().unwrap().scale_factor().ceil() as i32
+                });
+
+            if let Some(cursor) = &mut client.cursor {
+                cursor.enter(*serial, scale);
+            }
+        }
+
+        let pointer = &mut client.pointer;
+        match event {
+            WlPointerEvent::Enter {
+                serial: _,
+                surface,
+                surface_x,
+                surface_y,
+            } => {
+                pointer.focus = surface.data::<WindowId>().cloned();
+                pointer.position = Vec2::new(surface_x as f32, surface_y as f32);
+            }
+            WlPointerEvent::Motion {
+                time: _,
+                surface_x,
+                surface_y,
+            } => {
+                pointer.position = Vec2::new(surface_x as f32, surface_y as f32);
+            }
+            WlPointerEvent::Leave {
+                serial: _,
+                surface: _,
+            } => {
+                pointer.focus = None;
+                pointer.position = Vec2::MAX;
+                pointer.buttons = ButtonState::default();
+            }
+            WlPointerEvent::Button {
+                serial: _,
+                time: _,
+                button,
+                state,
+            } => {
+                let state = match state {
+                    WEnum::Value(state) => matches!(state, WlButtonState::Pressed),
+                    WEnum::Unknown(_) => unreachable!(),
+                };
+
+                match button {
+                    272 => pointer.buttons.left = state,
+                    273 => pointer.buttons.right = state,
+                    274 => pointer.buttons.middle = state,
+                    _ => {}
+                }
+            }
+            WlPointerEvent::Axis {
+                time: _,
+                axis,
+                value,
+            } => {
+                let value = value as f32;
+                match axis {
+                    WEnum::Value(Axis::VerticalScroll) => pointer.pending.delta.y += value,
+                    WEnum::Value(Axis::HorizontalScroll) => pointer.pending.delta.x += value,
+                    _ => {}
+                }
+            }
+            WlPointerEvent::AxisSource {
+                axis_source: WEnum::Value(source),
+            } => {
+                pointer.pending.source = Some(source.into());
+            }
+            WlPointerEvent::AxisStop { time: _, axis: _ } => {
+                pointer.pending.stop = true;
+            }
+            WlPointerEvent::AxisDiscrete { axis, discrete } => match axis {
+                WEnum::Value(Axis::VerticalScroll) => {
+                    pointer.value120.y += discrete * VALUE120_STEP;
+                }
+                WEnum::Value(Axis::HorizontalScroll) => {
+                    pointer.value120.x += discrete * VALUE120_STEP;
+                }
+                _ => {}
+            },
+            WlPointerEvent::AxisValue120 { axis, value120 } => match axis {
+                WEnum::Value(Axis::VerticalScroll) => pointer.value120.y += value120,
+                WEnum::Value(Axis::HorizontalScroll) => pointer.value120.x += value120,
+                _ => {}
+            },
+            WlPointerEvent::Frame => pointer.commit_frame(),
+            //WlPointerEvent::AxisRelativeDirection { axis, direction } => todo!(),
+            _ => {}
+        }
+
+        if proxy.version() < FRAME_SINCE {
+            pointer.commit_frame();
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pointer {
+    focus: Option<WindowId>,
+    position: Vec2,
+    buttons: ButtonState,
+
+    /// Scroll accumulated since the last [`Pointer::take_scroll`].
+    scroll: Scroll,
+    /// Axis events of the `wl_pointer.frame` being received.
+    pending: Scroll,
+    /// High resolution wheel steps not yet forming a whole detent.
+    value120: IVec2,
+}
+
+impl Pointer {
+    /// Window under the pointer.
+    #[must_use]
+    pub fn focus(&self) -> Option<&WindowId> {
+        self.focus.as_ref()
+    }
+
+    /// Position in the surface coordinates of the [focused](Pointer::focus) window.
+    #[must_use]
+    pub const fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    #[must_use]
+    pub const fn buttons(&self) -> ButtonState {
+        self.buttons
+    }
+
+    /// Returns the scroll accumulated since the previous call and resets it.
+    pub fn take_scroll(&mut self) -> Scroll {
+        std::mem::take(&mut self.scroll)
+    }
+
+    fn commit_frame(&mut self) {
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.discrete = self.value120 / VALUE120_STEP;
+        self.value120 -= pending.discrete * VALUE120_STEP;
+
+        self.scroll.delta += pending.delta;
+        self.scroll.discrete += pending.discrete;
+        self.scroll.stop |= pending.stop;
+        if pending.source.is_some() {
+            self.scroll.source = pending.source;
+        }
+    }
+}
+
+impl Default for Pointer {
+    fn default() -> Self {
+        Self {
+            focus: None,
+            position: Vec2::MAX,
+            buttons: ButtonState::default(),
+            scroll: Scroll::default(),
+            pending: Scroll::default(),
+            value120: IVec2::ZERO,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ButtonState {
+    left: bool,
+    right: bool,
+    middle: bool,
+}
+
+impl ButtonState {
+    #[must_use]
+    pub const fn left(&self) -> bool {
+        self.left
+    }
+
+    #[must_use]
+    pub const fn right(&self) -> bool {
+        self.right
+    }
+
+    #[must_use]
+    pub const fn middle(&self) -> bool {
+        self.middle
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollSource {
+    Wheel,
+    Finger,
+    Continuous,
+    WheelTilt,
+}
+
+impl From<AxisSource> for ScrollSource {
+    fn from(value: AxisSource) -> Self {
+        match value {
+            AxisSource::Finger => ScrollSource::Finger,
+            AxisSource::Continuous => ScrollSource::Continuous,
+            AxisSource::WheelTilt => ScrollSource::WheelTilt,
+            AxisSource::Wheel | _ => ScrollSource::Wheel,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Scroll {
+    delta: Vec2,
+    discrete: IVec2,
+    source: Option<ScrollSource>,
+    stop: bool,
+}
+
+impl Scroll {
+    /// Smooth scroll distance in surface coordinates, positive `y` scrolls down.
+    #[must_use]
+    pub const fn delta(&self) -> Vec2 {
+        self.delta
+    }
+
+    /// Whole wheel detents, positive `y` scrolls down.
+    #[must_use]
+    pub const fn discrete(&self) -> IVec2 {
+        self.discrete
+    }
+
+    #[must_use]
+    pub const fn source(&self) -> Option<ScrollSource> {
+        self.source
+    }
+
+    /// `true` when a finger or continuous scroll sequence has ended (kinetic scrolling may start).
+    #[must_use]
+    pub const fn stopped(&self) -> bool {
+        self.stop
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.delta == Vec2::ZERO && self.discrete == IVec2::ZERO && !self.stop
+    }
+}
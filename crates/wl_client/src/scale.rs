@@ -0,0 +1,72 @@
+use wayland_client::{Connection, Dispatch, QueueHandle};
+use wayland_protocols::wp::{
+    fractional_scale::v1::client::{
+        wp_fractional_scale_manager_v1::{
+            Event as WpFractionalScaleManagerV1Event, WpFractionalScaleManagerV1,
+        },
+        wp_fractional_scale_v1::{Event as WpFractionalScaleV1Event, WpFractionalScaleV1},
+    },
+    viewporter::client::{
+        wp_viewport::{Event as WpViewportEvent, WpViewport},
+        wp_viewporter::{Event as WpViewporterEvent, WpViewporter},
+    },
+};
+
+use crate::{window::WindowId, WlClient};
+
+/// Denominator of `wp_fractional_scale_v1.preferred_scale`.
+const FRACTIONAL_SCALE_DENOMINATOR: f64 = 120.0;
+
+impl Dispatch<WpFractionalScaleV1, WindowId> for WlClient {
+    fn event(
+        state: &mut Self,
+        _: &WpFractionalScaleV1,
+        event: WpFractionalScaleV1Event,
+        id: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let WpFractionalScaleV1Event::PreferredScale { scale } = event
+            && let Some(window) = state.windows.get(id.as_str())
+        {
+            let mut window = window.lock().unwrap();
+            window.set_fractional_scale(f64::from(scale) / FRACTIONAL_SCALE_DENOMINATOR);
+        }
+    }
+}
+
+impl Dispatch<WpFractionalScaleManagerV1, WindowId> for WlClient {
+    fn event(
+        _: &mut Self,
+        _: &WpFractionalScaleManagerV1,
+        _: WpFractionalScaleManagerV1Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WpViewporter, WindowId> for WlClient {
+    fn event(
+        _: &mut Self,
+        _: &WpViewporter,
+        _: WpViewporterEvent,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WpViewport, WindowId> for WlClient {
+    fn event(
+        _: &mut Self,
+        _: &WpViewport,
+        _: WpViewportEvent,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
@@ -0,0 +1,167 @@
+use wayland_client::{Connection, Dispatch, QueueHandle};
+use wayland_protocols::ext::session_lock::v1::client::{
+    ext_session_lock_manager_v1::{self, ExtSessionLockManagerV1},
+    ext_session_lock_surface_v1::{Event as ExtSessionLockSurfaceV1Event, ExtSessionLockSurfaceV1},
+    ext_session_lock_v1::{Event as ExtSessionLockV1Event, ExtSessionLockV1},
+};
+
+use crate::{window::WindowId, WlClient};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionLockEvent {
+    /// Every output shows the lock surfaces or is blanked, only the lock surfaces get input.
+    Locked,
+    /// The compositor refused to lock, e.g. another locker is running or it doesn't support
+    /// `ext_session_lock_manager_v1`, or ended the lock itself. The lock is destroyed, the lock
+    /// surfaces are no longer shown.
+    Finished,
+}
+
+/// Lock of the session, requested by creating the first [`WindowLayer::Lock`](crate::window::WindowLayer::Lock) window.
+#[derive(Default, Debug)]
+pub struct SessionLock {
+    lock: Option<ExtSessionLockV1>,
+    locked: bool,
+    events: Vec<SessionLockEvent>,
+}
+
+impl SessionLock {
+    /// Whether the compositor confirmed the lock, until it is unlocked.
+    #[must_use]
+    pub const fn locked(&self) -> bool {
+        self.locked
+    }
+
+    /// Returns the lock changes received since the previous call.
+    pub fn take_events(&mut self) -> Vec<SessionLockEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Lock the lock surfaces are created from, asking the compositor to lock on first use.
+    pub(crate) fn get_or_lock(
+        &mut self,
+        qh: &QueueHandle<WlClient>,
+        manager: &ExtSessionLockManagerV1,
+    ) -> &ExtSessionLockV1 {
+        self.lock
+            .get_or_insert_with(|| manager.lock(qh, WindowId::default()))
+    }
+
+    /// Reports the lock as finished right away when the compositor can't lock the session.
+    pub(crate) fn unsupported(&mut self) {
+        if !self.events.contains(&SessionLockEvent::Finished) {
+            self.events.push(SessionLockEvent::Finished);
+        }
+    }
+
+    /// Destroys the lock with the request the protocol expects in its current state.
+    fn release(&mut self) {
+        if let Some(lock) = self.lock.take() {
+            if self.locked {
+                lock.unlock_and_destroy();
+            } else {
+                lock.destroy();
+            }
+        }
+
+        self.locked = false;
+    }
+}
+
+impl WlClient {
+    /// Unlocks the session, or gives up locking it when the compositor didn't confirm yet.
+    ///
+    /// The lock windows should be destroyed, and the connection flushed with a roundtrip
+    /// before exiting so that the compositor receives the request.
+    pub fn unlock_session(&mut self) {
+        self.session_lock.release();
+    }
+
+    #[must_use]
+    pub fn session_lock(&self) -> &SessionLock {
+        &self.session_lock
+    }
+
+    pub fn session_lock_mut(&mut self) -> &mut SessionLock {
+        &mut self.session_lock
+    }
+}
+
+impl Dispatch<ExtSessionLockManagerV1, WindowId> for WlClient {
+    fn event(
+        _: &mut Self,
+        _: &ExtSessionLockManagerV1,
+        _: ext_session_lock_manager_v1::Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ExtSessionLockV1, WindowId> for WlClient {
+    fn event(
+        client: &mut Self,
+        proxy: &ExtSessionLockV1,
+        event: ExtSessionLockV1Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let session_lock = &mut client.session_lock;
+        // Events of a lock released in the meantime
+        if session_lock.lock.as_ref() != Some(proxy) {
+            return;
+        }
+
+        match event {
+            ExtSessionLockV1Event::Locked => {
+                session_lock.locked = true;
+                session_lock.events.push(SessionLockEvent::Locked);
+            }
+            // Answered with a plain destroy, even after `locked`
+            ExtSessionLockV1Event::Finished => {
+                if let Some(lock) = session_lock.lock.take() {
+                    lock.destroy();
+                }
+
+                session_lock.locked = false;
+                session_lock.events.push(SessionLockEvent::Finished);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ExtSessionLockSurfaceV1, WindowId> for WlClient {
+    fn event(
+        state: &mut Self,
+        surface: &ExtSessionLockSurfaceV1,
+        event: ExtSessionLockSurfaceV1Event,
+        id: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<WlClient>,
+    ) {
+        if let ExtSessionLockSurfaceV1Event::Configure {
+            serial,
+            width,
+            height,
+        } = event
+        {
+            surface.ack_configure(serial);
+            let Some(window) = state.windows.get_mut(id.as_str()) else {
+                return;
+            };
+
+            // The buffer must match the size of the output exactly
+            let mut window = window.lock().unwrap();
+            if width as i32 != window.width || height as i32 != window.height {
+                window.width = width as i32;
+                window.height = height as i32;
+                window.can_resize = true;
+            }
+
+            window.configure();
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use glam::IVec2;
+use wayland_client::{protocol::wl_seat::WlSeat, Connection, Dispatch, Proxy, QueueHandle};
+use wayland_protocols::wp::text_input::zv3::client::{
+    zwp_text_input_manager_v3::{self, ZwpTextInputManagerV3},
+    zwp_text_input_v3::{
+        ChangeCause, ContentHint, ContentPurpose, Event as ZwpTextInputV3Event, ZwpTextInputV3,
+    },
+};
+
+use crate::{window::WindowId, WlClient};
+
+/// Edit requested by the input method, to apply in order at the cursor of the focused text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextInputEvent {
+    /// Removes this many bytes before and after the cursor, from the text outside of the preedit.
+    DeleteSurrounding { before: u32, after: u32 },
+    /// Inserts the text at the cursor.
+    Commit(String),
+    /// Text being composed, shown at the cursor until the next preedit replaces it.
+    /// Empty once the composition ends.
+    ///
+    /// `cursor` is the byte range highlighted in the preedit, `None` hides the cursor.
+    Preedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+}
+
+/// State received until the next `zwp_text_input_v3.done`.
+#[derive(Default, Debug)]
+struct Pending {
+    preedit: Option<(String, Option<(usize, usize)>)>,
+    commit: Option<String>,
+    delete: Option<(u32, u32)>,
+}
+
+#[derive(Default, Debug)]
+pub struct TextInput {
+    input: Option<ZwpTextInputV3>,
+    /// Window with the keyboard focus, the only one the input method edits.
+    focus: Option<WindowId>,
+    enabled: bool,
+    cursor: Option<(IVec2, IVec2)>,
+    /// Shown since a previous `done`, cleared when no new preedit comes.
+    preedit: bool,
+
+    pending: Pending,
+    events: Vec<TextInputEvent>,
+}
+
+impl TextInput {
+    /// Window the input method is sending text to.
+    #[must_use]
+    pub fn focus(&self) -> Option<&WindowId> {
+        self.focus.as_ref()
+    }
+
+    #[must_use]
+    pub const fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the edits received since the previous call.
+    pub fn take_events(&mut self) -> Vec<TextInputEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub(crate) fn init(
+        &mut self,
+        qh: &QueueHandle<WlClient>,
+        seat: &WlSeat,
+        manager: Option<&ZwpTextInputManagerV3>,
+    ) {
+        if self.input.is_none()
+            && let Some(manager) = manager
+        {
+            self.input = Some(manager.get_text_input(seat, qh, WindowId::default()));
+        }
+    }
+
+    pub(crate) fn destroy(&mut self) {
+        if let Some(input) = self.input.take() {
+            input.destroy();
+        }
+
+        self.focus = None;
+        self.enabled = false;
+        self.cursor = None;
+    }
+
+    fn apply_pending(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        if let Some((before, after)) = pending.delete {
+            self.events
+                .push(TextInputEvent::DeleteSurrounding { before, after });
+        }
+
+        if let Some(text) = pending.commit {
+            self.events.push(TextInputEvent::Commit(text));
+        }
+
+        match pending.preedit {
+            Some((text, cursor)) => {
+                self.preedit = !text.is_empty();
+                self.events.push(TextInputEvent::Preedit { text, cursor });
+            }
+            None if self.preedit => {
+                self.preedit = false;
+                self.events.push(TextInputEvent::Preedit {
+                    text: String::new(),
+                    cursor: None,
+                });
+            }
+            None => {}
+        }
+    }
+}
+
+impl WlClient {
+    /// Lets the input method send text to the focused window, with the cursor of the edited
+    /// text at `position` with `size` in surface coordinates.
+    ///
+    /// Called again when the cursor moves so that the candidate popup follows it.
+    pub fn enable_text_input(&mut self, position: IVec2, size: IVec2) {
+        let text_input = &mut self.text_input;
+        let Some(input) = &text_input.input else {
+            return;
+        };
+
+        if text_input.focus.is_none()
+            || (text_input.enabled && text_input.cursor == Some((position, size)))
+        {
+            return;
+        }
+
+        if !text_input.enabled {
+            input.enable();
+            input.set_content_type(ContentHint::None, ContentPurpose::Normal);
+            input.set_text_change_cause(ChangeCause::Other);
+            text_input.enabled = true;
+        }
+
+        input.set_cursor_rectangle(position.x, position.y, size.x, size.y);
+        input.commit();
+        text_input.cursor = Some((position, size));
+    }
+
+    pub fn disable_text_input(&mut self) {
+        let text_input = &mut self.text_input;
+        let Some(input) = &text_input.input else {
+            return;
+        };
+
+        if !text_input.enabled {
+            return;
+        }
+
+        input.disable();
+        input.commit();
+        text_input.enabled = false;
+        text_input.cursor = None;
+    }
+
+    #[must_use]
+    pub fn text_input(&self) -> &TextInput {
+        &self.text_input
+    }
+
+    pub fn text_input_mut(&mut self) -> &mut TextInput {
+        &mut self.text_input
+    }
+}
+
+impl Dispatch<ZwpTextInputManagerV3, WindowId> for WlClient {
+    fn event(
+        _: &mut Self,
+        _: &ZwpTextInputManagerV3,
+        _: zwp_text_input_manager_v3::Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwpTextInputV3, WindowId> for WlClient {
+    fn event(
+        client: &mut Self,
+        _: &ZwpTextInputV3,
+        event: ZwpTextInputV3Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let text_input = &mut client.text_input;
+        match event {
+            ZwpTextInputV3Event::Enter { surface } => {
+                text_input.focus = surface.data::<WindowId>().cloned();
+            }
+            // The compositor forgets the enabled state, it must be sent again on enter
+            ZwpTextInputV3Event::Leave { surface: _ } => {
+                text_input.focus = None;
+                text_input.enabled = false;
+                text_input.cursor = None;
+                text_input.pending = Pending::default();
+                if text_input.preedit {
+                    text_input.preedit = false;
+                    text_input.events.push(TextInputEvent::Preedit {
+                        text: String::new(),
+                        cursor: None,
+                    });
+                }
+            }
+            ZwpTextInputV3Event::PreeditString {
+                text,
+                cursor_begin,
+                cursor_end,
+            } => {
+                let cursor = (cursor_begin >= 0 && cursor_end >= 0)
+                    .then_some((cursor_begin as usize, cursor_end as usize));
+                text_input.pending.preedit = Some((text.unwrap_or_default(), cursor));
+            }
+            ZwpTextInputV3Event::CommitString { text } => {
+                text_input.pending.commit = text;
+            }
+            ZwpTextInputV3Event::DeleteSurroundingText {
+                before_length,
+                after_length,
+            } => {
+                text_input.pending.delete = Some((before_length, after_length));
+            }
+            ZwpTextInputV3Event::Done { serial: _ } => text_input.apply_pending(),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use glam::Vec2;
+use wayland_client::{
+    protocol::wl_touch::{Event as WlTouchEvent, WlTouch},
+    Connection, Dispatch, Proxy, QueueHandle,
+};
+
+use crate::{window::WindowId, WlClient};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchPhase {
+    Started,
+    Moved,
+    Ended,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TouchPoint {
+    id: i32,
+    window: WindowId,
+    position: Vec2,
+    phase: TouchPhase,
+}
+
+impl TouchPoint {
+    /// Identifier of the touch sequence, unique among the currently active points.
+    #[must_use]
+    pub const fn id(&self) -> i32 {
+        self.id
+    }
+
+    #[must_use]
+    pub fn window(&self) -> &WindowId {
+        &self.window
+    }
+
+    /// Position in the surface coordinates of [`TouchPoint::window`].
+    #[must_use]
+    pub const fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    #[must_use]
+    pub const fn phase(&self) -> TouchPhase {
+        self.phase
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TrackedPoint {
+    point: TouchPoint,
+    /// Lifted before the `Started` phase was observed, reported as `Ended` on the next take.
+    lifted: bool,
+}
+
+#[derive(Default, Debug)]
+pub struct Touch {
+    points: Vec<TrackedPoint>,
+    pending: Vec<WlTouchEvent>,
+}
+
+impl Touch {
+    /// Returns every point touched since the previous call.
+    ///
+    /// A point is reported with [`TouchPhase::Started`] exactly once, even when it is lifted
+    /// before the next call, in which case the [`TouchPhase::Ended`] phase follows on the next take.
+    pub fn take_points(&mut self) -> Vec<TouchPoint> {
+        let points = self.points.iter().map(|p| p.point.clone()).collect();
+
+        self.points.retain_mut(|tracked| match tracked.point.phase {
+            TouchPhase::Started if tracked.lifted => {
+                tracked.point.phase = TouchPhase::Ended;
+                true
+            }
+            TouchPhase::Started => {
+                tracked.point.phase = TouchPhase::Moved;
+                true
+            }
+            TouchPhase::Moved => true,
+            TouchPhase::Ended | TouchPhase::Cancelled => false,
+        });
+
+        points
+    }
+
+    fn commit_frame(&mut self) {
+        for event in std::mem::take(&mut self.pending) {
+            match event {
+                WlTouchEvent::Down {
+                    surface, id, x, y, ..
+                } => {
+                    let Some(window) = surface.data::<WindowId>().cloned() else {
+                        continue;
+                    };
+
+                    self.points.retain(|p| p.point.id != id);
+                    self.points.push(TrackedPoint {
+                        point: TouchPoint {
+                            id,
+                            window,
+                            position: Vec2::new(x as f32, y as f32),
+                            phase: TouchPhase::Started,
+                        },
+                        lifted: false,
+                    });
+                }
+                WlTouchEvent::Motion { id, x, y, .. } => {
+                    if let Some(tracked) = self.points.iter_mut().find(|p| p.point.id == id) {
+                        tracked.point.position = Vec2::new(x as f32, y as f32);
+                    }
+                }
+                WlTouchEvent::Up { id, .. } => {
+                    if let Some(tracked) = self.points.iter_mut().find(|p| p.point.id == id) {
+                        if tracked.point.phase == TouchPhase::Started {
+                            tracked.lifted = true;
+                        } else {
+                            tracked.point.phase = TouchPhase::Ended;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn cancel(&mut self) {
+        self.pending.clear();
+        for tracked in &mut self.points {
+            tracked.point.phase = TouchPhase::Cancelled;
+        }
+    }
+}
+
+impl Dispatch<WlTouch, WindowId> for WlClient {
+    fn event(
+        client: &mut Self,
+        _: &WlTouch,
+        event: WlTouchEvent,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let WlTouchEvent::Down { serial, .. } = event {
+            client.serial = serial;
+        }
+
+        let touch = &mut client.touch;
+        match event {
+            WlTouchEvent::Down { .. } | WlTouchEvent::Motion { .. } | WlTouchEvent::Up { .. } => {
+                touch.pending.push(event);
+            }
+            WlTouchEvent::Frame => touch.commit_frame(),
+            WlTouchEvent::Cancel => touch.cancel(),
+            _ => {}
+        }
+    }
+}
@@ -1,36 +1,127 @@
 mod pool;
+mod popup;
+mod state;
 mod transform;
 
 use crate::WlClient;
+use glam::Vec2;
 pub(crate) use pool::ShmPool;
+pub use popup::{PopupAnchor, PopupGravity, PopupOptions};
+pub(crate) use popup::{PopupContext, PopupParent};
 use smithay_client_toolkit::reexports::protocols_wlr::layer_shell::v1::client::{
     zwlr_layer_shell_v1::{Layer, ZwlrLayerShellV1},
-    zwlr_layer_surface_v1::{Anchor, ZwlrLayerSurfaceV1},
+    zwlr_layer_surface_v1::{Anchor, KeyboardInteractivity, ZwlrLayerSurfaceV1},
 };
+pub use state::WindowState;
 use std::{ffi::c_void, ptr::NonNull, sync::Arc};
-use transform::Transform;
+pub use transform::Transform;
 use wayland_client::{
-    protocol::{wl_buffer::WlBuffer, wl_surface::WlSurface},
+    protocol::{
+        wl_buffer::WlBuffer, wl_output::WlOutput, wl_seat::WlSeat, wl_surface::WlSurface,
+    },
     Proxy, QueueHandle,
 };
-use wayland_protocols::xdg::shell::client::{
-    xdg_surface::XdgSurface, xdg_toplevel::XdgToplevel, xdg_wm_base::XdgWmBase,
+use wayland_protocols::{
+    ext::session_lock::v1::client::{
+        ext_session_lock_surface_v1::ExtSessionLockSurfaceV1, ext_session_lock_v1::ExtSessionLockV1,
+    },
+    wp::{
+        fractional_scale::v1::client::{
+            wp_fractional_scale_manager_v1::WpFractionalScaleManagerV1,
+            wp_fractional_scale_v1::WpFractionalScaleV1,
+        },
+        viewporter::client::{wp_viewport::WpViewport, wp_viewporter::WpViewporter},
+    },
+    xdg::{
+        decoration::zv1::client::{
+            zxdg_decoration_manager_v1::ZxdgDecorationManagerV1,
+            zxdg_toplevel_decoration_v1::{Mode as DecorationMode, ZxdgToplevelDecorationV1},
+        },
+        shell::client::{
+            xdg_popup::XdgPopup,
+            xdg_surface::XdgSurface,
+            xdg_toplevel::{ResizeEdge, XdgToplevel},
+            xdg_wm_base::XdgWmBase,
+        },
+    },
 };
 
 pub type WindowId = Arc<String>;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowEvent {
+    /// The user asked to close a desktop window, e.g. with the titlebar close button.
+    CloseRequested(WindowId),
+    /// The compositor closed a layer window, its surface must be destroyed.
+    Closed(WindowId),
+    /// A popup was dismissed, e.g. by a click outside of it, and must be destroyed.
+    PopupDone(WindowId),
+    /// The compositor changed the state of a desktop window.
+    StateChanged(WindowId, WindowState),
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct DesktopOptions {
     pub title: String,
+    /// Identifies the application to the compositor, usually the name of its desktop file.
+    pub app_id: String,
     pub resizable: bool,
+    /// Asks the compositor to draw the titlebar and borders.
     pub decorations: bool,
 }
 
+/// Layer-shell window options.
+///
+/// A width or height of `0` in the window request stretches the surface between the opposite
+/// edges it is anchored to, the final size is then provided by the compositor.
 #[derive(Debug, Clone)]
 pub struct SpecialOptions {
     pub anchor: Anchor,
     pub exclusive_zone: u32,
     pub target: TargetMonitor,
+    /// Distance from the anchored edges.
+    pub margin: Margin,
+    pub keyboard_interactivity: KeyboardInteractivity,
+    /// Namespace reported to the compositor for its window rules, defaults to the window id.
+    pub namespace: Option<String>,
+}
+
+impl Default for SpecialOptions {
+    fn default() -> Self {
+        Self {
+            anchor: Anchor::empty(),
+            exclusive_zone: 0,
+            target: TargetMonitor::default(),
+            margin: Margin::default(),
+            keyboard_interactivity: KeyboardInteractivity::None,
+            namespace: None,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Margin {
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+    pub left: i32,
+}
+
+impl Margin {
+    #[must_use]
+    pub const fn new(top: i32, right: i32, bottom: i32, left: i32) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    #[must_use]
+    pub const fn all(value: i32) -> Self {
+        Self::new(value, value, value, value)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -40,6 +131,36 @@ pub enum WindowLayer {
     Bottom(SpecialOptions),
     Overlay(SpecialOptions),
     Background(SpecialOptions),
+    Popup(PopupOptions),
+    /// Shown on every output while the session is locked, in place of all other windows.
+    ///
+    /// Creating the first one asks the compositor to lock the session, the lock stays until
+    /// [`WlClient::unlock_session`] even if the windows are closed.
+    Lock,
+}
+
+impl WindowLayer {
+    /// Monitor requested by a layer-shell window, `None` for desktop windows.
+    #[must_use]
+    pub const fn target(&self) -> Option<&TargetMonitor> {
+        match self {
+            WindowLayer::Desktop(_) | WindowLayer::Popup(_) => None,
+            WindowLayer::Lock => Some(&TargetMonitor::All),
+            WindowLayer::Top(options)
+            | WindowLayer::Bottom(options)
+            | WindowLayer::Overlay(options)
+            | WindowLayer::Background(options) => Some(&options.target),
+        }
+    }
+
+    /// Id of the window a popup is attached to.
+    #[must_use]
+    pub fn parent(&self) -> Option<&str> {
+        match self {
+            WindowLayer::Popup(options) => Some(&options.parent),
+            _ => None,
+        }
+    }
 }
 
 impl Default for WindowLayer {
@@ -50,10 +171,12 @@ impl Default for WindowLayer {
 
 #[derive(Default, Debug, Clone)]
 pub enum TargetMonitor {
+    /// Output chosen by the compositor, usually the focused one.
     #[default]
     Primary,
     Name(String),
     Index(usize),
+    /// One surface per output.
     All,
 }
 
@@ -62,12 +185,29 @@ struct Unused {
     layer_surface: Option<ZwlrLayerSurfaceV1>,
     xdg_surface: Option<XdgSurface>,
     xdg_toplevel: Option<XdgToplevel>,
+    xdg_popup: Option<XdgPopup>,
+    lock_surface: Option<ExtSessionLockSurfaceV1>,
+    decoration: Option<ZxdgToplevelDecorationV1>,
+    fractional_scale: Option<WpFractionalScaleV1>,
+    viewport: Option<WpViewport>,
+}
+
+/// Buffer in the window's pool, the compositor may read it until it releases it.
+#[derive(Debug)]
+struct ShmBuffer {
+    buffer: WlBuffer,
+    size: (i32, i32),
+    busy: bool,
 }
 
 #[derive(Debug)]
 pub struct Window {
     surface: WlSurface,
-    buffer: WlBuffer,
+    /// Laid out one after another in the pool, a second one is added when the compositor
+    /// still holds the first.
+    buffers: Vec<ShmBuffer>,
+    /// Buffer drawn to by [`Window::buffer_mut`] and attached by [`Window::draw`].
+    current: usize,
     pool: ShmPool,
     qh: QueueHandle<WlClient>,
     pub id: Arc<String>,
@@ -81,48 +221,194 @@ pub struct Window {
     //Window transformation
     pub scale: i32,
     pub transform: Transform,
+    /// Scale from `wp_fractional_scale_v1`, takes precedence over [`Window::scale`].
+    fractional_scale: Option<f64>,
+
+    pub(crate) state: WindowState,
 
     pub(crate) can_draw: bool,
     pub(crate) can_resize: bool,
+    /// Configured since the last frame, which must follow the configure.
+    pub(crate) configured: bool,
+    /// Drawn by the GPU, the SHM buffer is only attached until then.
+    gpu_rendered: bool,
 
     unused: Unused,
 }
 
 impl Window {
+    /// Replaces the buffers once the physical size changed.
     pub fn resize_buffer_if_needed(&mut self) {
-        self.buffer = self
+        let size = self.physical_size();
+        if self
+            .buffers
+            .first()
+            .is_some_and(|buffer| buffer.size == size)
+        {
+            return;
+        }
+
+        for buffer in self.buffers.drain(..) {
+            buffer.buffer.destroy();
+        }
+
+        self.current = 0;
+        self.add_buffer();
+    }
+
+    fn add_buffer(&mut self) {
+        let (width, height) = self.physical_size();
+        let offset = self.buffers.len() * self.buffer_len();
+        let size = (offset + self.buffer_len()) as u64;
+        if self.pool.need_resize(size) {
+            self.pool.resize(size);
+        }
+
+        let buffer = self
             .pool
-            .create_buffer(0, self.width, self.height, &self.qh, &self.id);
+            .create_buffer(offset as i32, width, height, &self.qh, &self.id);
+        self.buffers.push(ShmBuffer {
+            buffer,
+            size: (width, height),
+            busy: false,
+        });
+    }
+
+    /// Lets the buffer be drawn to again, once the compositor is done reading it.
+    pub(crate) fn release_buffer(&mut self, buffer: &WlBuffer) {
+        if let Some(buffer) = self.buffers.iter_mut().find(|b| b.buffer == *buffer) {
+            buffer.busy = false;
+        }
+    }
+
+    fn buffer_len(&self) -> usize {
+        let (width, height) = self.physical_size();
+        width as usize * 4 * height as usize
     }
 
     pub fn destroy(self) {
-        self.buffer.destroy();
-        self.pool.destroy();
-        self.surface.destroy();
+        // Role objects go before the surface they are assigned to
+        if let Some(decoration) = self.unused.decoration {
+            decoration.destroy();
+        }
+
+        if let Some(xdg_popup) = self.unused.xdg_popup {
+            xdg_popup.destroy();
+        }
+
+        if let Some(xdg_toplevel) = self.unused.xdg_toplevel {
+            xdg_toplevel.destroy();
+        }
+
+        if let Some(surface) = self.unused.xdg_surface {
+            surface.destroy();
+        }
+
         if let Some(surface) = self.unused.layer_surface {
             surface.destroy();
         }
 
-        if let Some(surface) = self.unused.xdg_surface {
+        if let Some(surface) = self.unused.lock_surface {
             surface.destroy();
         }
 
-        if let Some(xdg_toplevel) = self.unused.xdg_toplevel {
-            xdg_toplevel.destroy();
+        if let Some(viewport) = self.unused.viewport {
+            viewport.destroy();
+        }
+
+        if let Some(fractional_scale) = self.unused.fractional_scale {
+            fractional_scale.destroy();
+        }
+
+        for buffer in self.buffers {
+            buffer.buffer.destroy();
+        }
+
+        self.pool.destroy();
+        self.surface.destroy();
+    }
+
+    /// Role object popups opened from this window are attached to.
+    pub(crate) fn popup_parent(&self) -> Option<PopupParent> {
+        if let Some(surface) = &self.unused.xdg_surface {
+            return Some(PopupParent::Xdg(surface.clone()));
+        }
+
+        self.unused
+            .layer_surface
+            .as_ref()
+            .map(|surface| PopupParent::Layer(surface.clone()))
+    }
+
+    /// Opts the surface into fractional scaling, the buffer is then mapped to the logical size
+    /// through a viewport instead of `wl_surface.set_buffer_scale`.
+    pub(crate) fn init_fractional_scale(
+        &mut self,
+        manager: &WpFractionalScaleManagerV1,
+        viewporter: &WpViewporter,
+    ) {
+        self.unused.fractional_scale =
+            Some(manager.get_fractional_scale(&self.surface, &self.qh, self.id.clone()));
+        self.unused.viewport =
+            Some(viewporter.get_viewport(&self.surface, &self.qh, self.id.clone()));
+    }
+
+    /// Ratio between buffer pixels and surface coordinates.
+    #[must_use]
+    pub fn scale_factor(&self) -> f64 {
+        self.fractional_scale.unwrap_or(f64::from(self.scale))
+    }
+
+    /// Size of the buffer in pixels, at least one pixel while the size is not configured yet.
+    #[must_use]
+    pub fn physical_size(&self) -> (i32, i32) {
+        let scale = self.scale_factor();
+        (
+            ((f64::from(self.width) * scale).round() as i32).max(1),
+            ((f64::from(self.height) * scale).round() as i32).max(1),
+        )
+    }
+
+    pub(crate) fn set_scale(&mut self, scale: i32) {
+        if self.scale != scale {
+            self.scale = scale;
+            self.can_resize |= self.fractional_scale.is_none();
+        }
+    }
+
+    pub(crate) fn set_fractional_scale(&mut self, scale: f64) {
+        if self.fractional_scale != Some(scale) {
+            self.fractional_scale = Some(scale);
+            self.can_resize = true;
+        }
+    }
+
+    /// Tells the compositor how the next buffer maps to the surface.
+    ///
+    /// Must be called with the buffer of [`Window::physical_size`] being attached.
+    pub fn apply_scale(&mut self) {
+        if let Some(viewport) = &self.unused.viewport {
+            viewport.set_destination(self.width, self.height);
+        } else {
+            self.surface.set_buffer_scale(self.scale);
         }
     }
 
     #[must_use]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         ls: Option<&ZwlrLayerShellV1>, // 'Some' when WindowLayer is not a WindowLayer::Desktop
         xdg_wm_base: Option<&XdgWmBase>, // 'Some' when WindowLayer is a WindowLayer::Desktop
+        output: Option<&WlOutput>,
+        popup: Option<PopupContext>, // 'Some' when WindowLayer is a WindowLayer::Popup
+        lock: Option<&ExtSessionLockV1>, // 'Some' when WindowLayer is a WindowLayer::Lock and the compositor supports it
+        decoration_manager: Option<&ZxdgDecorationManagerV1>,
 
         qh: QueueHandle<WlClient>,
         id: WindowId,
 
         surface: WlSurface,
         pool: ShmPool,
-        buffer: WlBuffer,
 
         width: i32,
         height: i32,
@@ -130,7 +416,8 @@ impl Window {
     ) -> Self {
         let mut instance = Self {
             surface,
-            buffer,
+            buffers: vec![],
+            current: 0,
             pool,
             qh,
             id,
@@ -139,57 +426,225 @@ impl Window {
             height,
             scale: 1,
             transform: Transform::Normal0,
+            fractional_scale: None,
             can_draw: false,
+            state: WindowState::empty(),
             can_resize: false,
+            configured: false,
+            gpu_rendered: false,
             unused: Unused::default(),
         };
 
-        instance.init(ls, xdg_wm_base);
-        instance.draw();
-        instance.frame();
+        instance.resize_buffer_if_needed();
+        instance.init(ls, xdg_wm_base, output, popup, lock, decoration_manager);
+        // Lock surfaces get their first buffer once configured
+        if !matches!(instance.layer, WindowLayer::Lock) {
+            instance.draw();
+            instance.frame();
+        }
         instance
     }
 
-    fn init(&mut self, ls: Option<&ZwlrLayerShellV1>, xdg_wm_base: Option<&XdgWmBase>) {
+    fn init(
+        &mut self,
+        ls: Option<&ZwlrLayerShellV1>,
+        xdg_wm_base: Option<&XdgWmBase>,
+        output: Option<&WlOutput>,
+        popup: Option<PopupContext>,
+        lock: Option<&ExtSessionLockV1>,
+        decoration_manager: Option<&ZxdgDecorationManagerV1>,
+    ) {
         match self.layer.clone() {
-            WindowLayer::Desktop(_) => self.init_desktop(xdg_wm_base.unwrap()),
-            WindowLayer::Top(options) => self.init_layer_shell(ls.unwrap(), Layer::Top, &options),
+            WindowLayer::Desktop(options) => {
+                self.init_desktop(xdg_wm_base.unwrap(), &options, decoration_manager);
+            }
+            WindowLayer::Popup(options) => {
+                self.init_popup(xdg_wm_base.unwrap(), &options, popup.unwrap());
+            }
+            WindowLayer::Top(options) => {
+                self.init_layer_shell(ls.unwrap(), Layer::Top, &options, output);
+            }
             WindowLayer::Bottom(options) => {
-                self.init_layer_shell(ls.unwrap(), Layer::Bottom, &options);
+                self.init_layer_shell(ls.unwrap(), Layer::Bottom, &options, output);
             }
             WindowLayer::Overlay(options) => {
-                self.init_layer_shell(ls.unwrap(), Layer::Overlay, &options);
+                self.init_layer_shell(ls.unwrap(), Layer::Overlay, &options, output);
             }
             WindowLayer::Background(options) => {
-                self.init_layer_shell(ls.unwrap(), Layer::Background, &options);
+                self.init_layer_shell(ls.unwrap(), Layer::Background, &options, output);
+            }
+            // Without ext_session_lock_manager_v1 or an output to cover, e.g. one unplugged
+            // meanwhile, the surface gets no role and is never shown
+            WindowLayer::Lock => {
+                self.unused.lock_surface = lock.zip(output).map(|(lock, output)| {
+                    lock.get_lock_surface(&self.surface, output, &self.qh, self.id.clone())
+                });
             }
         }
     }
 
-    fn init_layer_shell(&mut self, ls: &ZwlrLayerShellV1, layer: Layer, options: &SpecialOptions) {
+    fn init_layer_shell(
+        &mut self,
+        ls: &ZwlrLayerShellV1,
+        layer: Layer,
+        options: &SpecialOptions,
+        output: Option<&WlOutput>,
+    ) {
+        let namespace = options
+            .namespace
+            .clone()
+            .unwrap_or_else(|| self.id.to_string());
+
         let layer_surface = ls.get_layer_surface(
             &self.surface,
-            None, //TODO fix
+            output,
             layer,
-            self.id.as_ref().into(),
+            namespace,
             &self.qh,
             self.id.clone(),
         );
 
+        let margin = options.margin;
         layer_surface.set_size(self.width as u32, self.height as u32);
         layer_surface.set_anchor(options.anchor);
         layer_surface.set_exclusive_zone(options.exclusive_zone as i32);
+        layer_surface.set_margin(margin.top, margin.right, margin.bottom, margin.left);
+        layer_surface.set_keyboard_interactivity(options.keyboard_interactivity);
 
         self.unused.layer_surface = Some(layer_surface);
     }
 
-    fn init_desktop(&mut self, xdg_wm_base: &XdgWmBase) {
+    fn init_desktop(
+        &mut self,
+        xdg_wm_base: &XdgWmBase,
+        options: &DesktopOptions,
+        decoration_manager: Option<&ZxdgDecorationManagerV1>,
+    ) {
         let xdg_surface = xdg_wm_base.get_xdg_surface(&self.surface, &self.qh, self.id.clone());
         let xdg_toplevel = xdg_surface.get_toplevel(&self.qh, self.id.clone());
+        xdg_toplevel.set_title(options.title.clone());
+        if !options.app_id.is_empty() {
+            xdg_toplevel.set_app_id(options.app_id.clone());
+        }
+
+        // Without the manager the compositor expects client-side decorations
+        if let Some(manager) = decoration_manager {
+            let decoration =
+                manager.get_toplevel_decoration(&xdg_toplevel, &self.qh, self.id.clone());
+            decoration.set_mode(if options.decorations {
+                DecorationMode::ServerSide
+            } else {
+                DecorationMode::ClientSide
+            });
+            self.unused.decoration = Some(decoration);
+        }
+
         self.unused.xdg_surface = Some(xdg_surface);
         self.unused.xdg_toplevel = Some(xdg_toplevel);
     }
 
+    fn init_popup(&mut self, xdg_wm_base: &XdgWmBase, options: &PopupOptions, popup: PopupContext) {
+        let positioner = xdg_wm_base.create_positioner(&self.qh, self.id.clone());
+        options.apply(&positioner, self.width, self.height);
+
+        let xdg_surface = xdg_wm_base.get_xdg_surface(&self.surface, &self.qh, self.id.clone());
+        let xdg_popup = match &popup.parent {
+            PopupParent::Xdg(parent) => {
+                xdg_surface.get_popup(Some(parent), &positioner, &self.qh, self.id.clone())
+            }
+            PopupParent::Layer(parent) => {
+                let xdg_popup = xdg_surface.get_popup(None, &positioner, &self.qh, self.id.clone());
+                parent.get_popup(&xdg_popup);
+                xdg_popup
+            }
+        };
+        positioner.destroy();
+
+        if let Some((seat, serial)) = &popup.grab {
+            xdg_popup.grab(seat, *serial);
+        }
+
+        self.unused.xdg_surface = Some(xdg_surface);
+        self.unused.xdg_popup = Some(xdg_popup);
+    }
+
+    #[must_use]
+    pub const fn state(&self) -> WindowState {
+        self.state
+    }
+
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        let title = title.into();
+        if let Some(xdg_toplevel) = &self.unused.xdg_toplevel {
+            xdg_toplevel.set_title(title.clone());
+        }
+
+        if let WindowLayer::Desktop(options) = &mut self.layer {
+            options.title = title;
+        }
+    }
+
+    pub fn set_maximized(&self, maximized: bool) {
+        if let Some(xdg_toplevel) = &self.unused.xdg_toplevel {
+            if maximized {
+                xdg_toplevel.set_maximized();
+            } else {
+                xdg_toplevel.unset_maximized();
+            }
+        }
+    }
+
+    pub fn set_minimized(&self) {
+        if let Some(xdg_toplevel) = &self.unused.xdg_toplevel {
+            xdg_toplevel.set_minimized();
+        }
+    }
+
+    pub fn set_fullscreen(&self, fullscreen: bool) {
+        if let Some(xdg_toplevel) = &self.unused.xdg_toplevel {
+            if fullscreen {
+                xdg_toplevel.set_fullscreen(None);
+            } else {
+                xdg_toplevel.unset_fullscreen();
+            }
+        }
+    }
+
+    /// Applied with the next commit, `0` removes the limit.
+    pub fn set_min_size(&self, width: i32, height: i32) {
+        if let Some(xdg_toplevel) = &self.unused.xdg_toplevel {
+            xdg_toplevel.set_min_size(width, height);
+        }
+    }
+
+    /// Applied with the next commit, `0` removes the limit.
+    pub fn set_max_size(&self, width: i32, height: i32) {
+        if let Some(xdg_toplevel) = &self.unused.xdg_toplevel {
+            xdg_toplevel.set_max_size(width, height);
+        }
+    }
+
+    /// Lets the user drag the window, `serial` must come from the press starting the drag.
+    pub fn start_move(&self, seat: &WlSeat, serial: u32) {
+        if let Some(xdg_toplevel) = &self.unused.xdg_toplevel {
+            xdg_toplevel._move(seat, serial);
+        }
+    }
+
+    /// Lets the user resize the window from `edge`, see [`Window::start_move`].
+    pub fn start_resize(&self, seat: &WlSeat, serial: u32, edge: ResizeEdge) {
+        if let Some(xdg_toplevel) = &self.unused.xdg_toplevel {
+            xdg_toplevel.resize(seat, serial, edge);
+        }
+    }
+
+    /// Shows the compositor window menu at `position`, in surface coordinates.
+    pub fn show_window_menu(&self, seat: &WlSeat, serial: u32, position: Vec2) {
+        if let Some(xdg_toplevel) = &self.unused.xdg_toplevel {
+            xdg_toplevel.show_window_menu(seat, serial, position.x as i32, position.y as i32);
+        }
+    }
+
     #[must_use]
     pub fn can_draw(&self) -> bool {
         self.can_draw
@@ -200,22 +655,29 @@ impl Window {
     }
 
     pub fn damage_buffer(&mut self) {
-        self.surface.damage_buffer(0, 0, self.width, self.height);
+        let (width, height) = self.physical_size();
+        self.surface.damage_buffer(0, 0, width, height);
     }
 
+    /// Every commit asks for a frame callback, which lets the window draw again once the
+    /// compositor has shown it.
     pub fn commit(&mut self) {
-        self.surface.damage_buffer(0, 0, self.width, self.height);
+        self.frame();
+        self.damage_buffer();
         self.surface.commit();
         self.can_draw = false;
     }
 
+    /// Attaches the buffer last drawn to, it stays busy until the compositor releases it.
     pub fn draw(&mut self) {
-        self.surface.attach(Some(&self.buffer), 0, 0);
+        let buffer = &mut self.buffers[self.current];
+        buffer.busy = true;
+        self.surface.attach(Some(&buffer.buffer), 0, 0);
         self.commit();
     }
 
     pub fn resize_pool_if_needed(&mut self) {
-        let size = (self.width as u64 * 4) * self.height as u64;
+        let size = (self.buffer_len() * self.buffers.len().max(1)) as u64;
         if self.pool.need_resize(size) {
             self.pool.resize(size);
         }
@@ -229,12 +691,36 @@ impl Window {
         self.pool.write_pixel(x, y, width, pixel);
     }
 
+    /// Pixels of the buffer attached by [`Window::draw`], `Argb8888` rows of
+    /// [`Window::physical_size`].
+    ///
+    /// Never the one the compositor is still reading, another buffer is used meanwhile.
+    pub fn buffer_mut(&mut self) -> &mut [u8] {
+        if self.buffers[self.current].busy {
+            self.current = match self.buffers.iter().position(|buffer| !buffer.busy) {
+                Some(index) => index,
+                None => {
+                    self.add_buffer();
+                    self.buffers.len() - 1
+                }
+            };
+        }
+
+        let len = self.buffer_len();
+        self.pool.data_mut(self.current * len, len)
+    }
+
     pub fn draw_text_at(&mut self, x: usize, y: usize, coverage: f32) {
+        let (width, height) = self.physical_size();
         self.pool
-            .draw_text_at(x, y, self.width as usize, self.height as usize, coverage);
+            .draw_text_at(x, y, width as usize, height as usize, coverage);
     }
 
     #[must_use]
+    pub(crate) fn surface(&self) -> &WlSurface {
+        &self.surface
+    }
+
     pub fn as_ptr(&self) -> NonNull<c_void> {
         NonNull::new(self.surface.id().as_ptr().cast::<c_void>()).unwrap()
     }
@@ -247,4 +733,29 @@ impl Window {
     pub fn set_resized(&mut self) {
         self.can_resize = false;
     }
+
+    /// Whether the window was configured since the previous call, and must be drawn again.
+    pub fn take_configured(&mut self) -> bool {
+        std::mem::take(&mut self.configured)
+    }
+
+    /// Leaves the surface to the GPU renderer, configures no longer attach the SHM buffer
+    /// over its frames.
+    pub fn set_gpu_rendered(&mut self) {
+        self.gpu_rendered = true;
+    }
+
+    /// Answers a configure of a layer or lock surface with a frame.
+    ///
+    /// The SHM buffer holds the last software frame. GPU windows draw right away instead,
+    /// without waiting for a frame callback, as the surface may not be mapped yet.
+    pub(crate) fn configure(&mut self) {
+        self.resize_buffer_if_needed();
+        self.configured = true;
+        if self.gpu_rendered {
+            self.can_draw = true;
+        } else {
+            self.draw();
+        }
+    }
 }
@@ -78,6 +78,12 @@ impl ShmPool {
             let new_size = new_size + new_size / 2;
             self.mmap.file.set_len(new_size).unwrap();
             self.mmap.file_size = new_size;
+            // The mapping keeps the length of the file it was created with
+            self.mmap.inner = unsafe {
+                memmap2::MmapOptions::new()
+                    .map_mut(&self.mmap.file)
+                    .unwrap()
+            };
             file_modified = true;
         }
 
@@ -144,6 +150,11 @@ impl ShmPool {
         buffer[offset + 3] = pixel.3;
     }
 
+    /// `len` bytes of the pool from `offset`, where a buffer was created.
+    pub fn data_mut(&mut self, offset: usize, len: usize) -> &mut [u8] {
+        &mut self.mmap.inner[offset..offset + len]
+    }
+
     pub fn clear(&mut self) {
         let buffer = &mut self.mmap.inner;
         let b = 0;
@@ -0,0 +1,74 @@
+use glam::{IVec2, Vec2};
+use smithay_client_toolkit::reexports::protocols_wlr::layer_shell::v1::client::zwlr_layer_surface_v1::ZwlrLayerSurfaceV1;
+use wayland_client::protocol::wl_seat::WlSeat;
+pub use wayland_protocols::xdg::shell::client::xdg_positioner::{
+    Anchor as PopupAnchor, Gravity as PopupGravity,
+};
+use wayland_protocols::xdg::shell::client::{
+    xdg_positioner::{ConstraintAdjustment, XdgPositioner},
+    xdg_surface::XdgSurface,
+};
+
+#[derive(Debug, Clone)]
+pub struct PopupOptions {
+    /// Id of the window the popup is attached to.
+    pub parent: String,
+    /// Position of the anchor rectangle in the parent surface coordinates.
+    pub anchor_position: Vec2,
+    pub anchor_size: Vec2,
+    /// Edge or corner of the anchor rectangle the popup is attached to.
+    pub anchor: PopupAnchor,
+    /// Direction the popup extends to from the anchor point.
+    pub gravity: PopupGravity,
+    pub offset: IVec2,
+    /// Grabs the seat so a click outside of the popup dismisses it.
+    pub grab: bool,
+}
+
+impl PopupOptions {
+    /// Popup opened below the anchor rectangle, like a dropdown.
+    #[must_use]
+    pub fn new(parent: impl Into<String>, anchor_position: Vec2, anchor_size: Vec2) -> Self {
+        Self {
+            parent: parent.into(),
+            anchor_position,
+            anchor_size,
+            anchor: PopupAnchor::BottomLeft,
+            gravity: PopupGravity::BottomRight,
+            offset: IVec2::ZERO,
+            grab: true,
+        }
+    }
+
+    pub(crate) fn apply(&self, positioner: &XdgPositioner, width: i32, height: i32) {
+        let position = self.anchor_position.round().as_ivec2();
+        let size = self.anchor_size.round().as_ivec2().max(IVec2::ONE);
+
+        positioner.set_size(width.max(1), height.max(1));
+        positioner.set_anchor_rect(position.x, position.y, size.x, size.y);
+        positioner.set_anchor(self.anchor);
+        positioner.set_gravity(self.gravity);
+        positioner.set_offset(self.offset.x, self.offset.y);
+        positioner.set_constraint_adjustment(
+            ConstraintAdjustment::FlipX
+                | ConstraintAdjustment::FlipY
+                | ConstraintAdjustment::SlideX
+                | ConstraintAdjustment::SlideY,
+        );
+    }
+}
+
+/// Role object of the window a popup is attached to.
+#[derive(Debug, Clone)]
+pub enum PopupParent {
+    Xdg(XdgSurface),
+    Layer(ZwlrLayerSurfaceV1),
+}
+
+/// Everything needed to assign the popup role when the popup window is created.
+#[derive(Debug, Clone)]
+pub struct PopupContext {
+    pub parent: PopupParent,
+    /// Seat and serial of the input event which opened the popup, used for the grab.
+    pub grab: Option<(WlSeat, u32)>,
+}
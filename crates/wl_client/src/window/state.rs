@@ -0,0 +1,50 @@
+use bitflags::bitflags;
+use wayland_protocols::xdg::shell::client::xdg_toplevel::State;
+
+bitflags! {
+    /// State of a desktop window, as configured by the compositor.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct WindowState: u16 {
+        const Maximized    = 1 << 0;
+        const Fullscreen   = 1 << 1;
+        const Resizing     = 1 << 2;
+        const Activated    = 1 << 3;
+        const TiledLeft    = 1 << 4;
+        const TiledRight   = 1 << 5;
+        const TiledTop     = 1 << 6;
+        const TiledBottom  = 1 << 7;
+        const Suspended    = 1 << 8;
+        /// Decorations are drawn by the compositor, the window must not draw its own titlebar.
+        const ServerDecorations = 1 << 9;
+
+        const Tiled = Self::TiledLeft.bits()
+            | Self::TiledRight.bits()
+            | Self::TiledTop.bits()
+            | Self::TiledBottom.bits();
+    }
+}
+
+impl WindowState {
+    /// Parses the `states` array of `xdg_toplevel.configure`.
+    pub(crate) fn from_configure(states: &[u8]) -> Self {
+        states
+            .chunks_exact(4)
+            .map(|chunk| u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .filter_map(|state| State::try_from(state).ok())
+            .fold(Self::empty(), |flags, state| {
+                flags
+                    | match state {
+                        State::Maximized => Self::Maximized,
+                        State::Fullscreen => Self::Fullscreen,
+                        State::Resizing => Self::Resizing,
+                        State::Activated => Self::Activated,
+                        State::TiledLeft => Self::TiledLeft,
+                        State::TiledRight => Self::TiledRight,
+                        State::TiledTop => Self::TiledTop,
+                        State::TiledBottom => Self::TiledBottom,
+                        State::Suspended => Self::Suspended,
+                        _ => Self::empty(),
+                    }
+            })
+    }
+}
@@ -1,7 +1,8 @@
 use wayland_client::{protocol::wl_output::Transform as WTransform, WEnum};
 
-#[derive(Debug)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Transform {
+    #[default]
     Normal0,
     Normal90,
     Normal180,
@@ -13,6 +14,17 @@ pub enum Transform {
     Custom(u32),
 }
 
+impl Transform {
+    /// `true` when the content is rotated by 90 or 270 degrees, swapping width and height.
+    #[must_use]
+    pub const fn is_rotated(&self) -> bool {
+        matches!(
+            self,
+            Self::Normal90 | Self::Normal270 | Self::Flipped90 | Self::Flipped270
+        )
+    }
+}
+
 impl From<WEnum<WTransform>> for Transform {
     fn from(value: WEnum<WTransform>) -> Self {
         match value {
@@ -0,0 +1,373 @@
+use bitflags::bitflags;
+use wayland_client::{event_created_child, Connection, Dispatch, QueueHandle, WEnum};
+use wayland_protocols::ext::workspace::v1::client::{
+    ext_workspace_group_handle_v1::{
+        Event as ExtWorkspaceGroupHandleV1Event, ExtWorkspaceGroupHandleV1,
+    },
+    ext_workspace_handle_v1::{Event as ExtWorkspaceHandleV1Event, ExtWorkspaceHandleV1, State},
+    ext_workspace_manager_v1::{self, Event as ExtWorkspaceManagerV1Event, ExtWorkspaceManagerV1},
+};
+
+use crate::{window::WindowId, WlClient};
+
+bitflags! {
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct WorkspaceState: u8 {
+        const Active = 1 << 0;
+        /// The workspace asks for attention, e.g. a window on it is urgent.
+        const Urgent = 1 << 1;
+        /// Should not be shown, e.g. a scratchpad.
+        const Hidden = 1 << 2;
+    }
+}
+
+impl From<State> for WorkspaceState {
+    fn from(value: State) -> Self {
+        let mut state = Self::empty();
+        state.set(Self::Active, value.contains(State::Active));
+        state.set(Self::Urgent, value.contains(State::Urgent));
+        state.set(Self::Hidden, value.contains(State::Hidden));
+        state
+    }
+}
+
+/// Workspace of the compositor, e.g. for a pager in a bar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    id: u64,
+    name: String,
+    coordinates: Vec<u32>,
+    state: WorkspaceState,
+    group: Option<u64>,
+    outputs: Vec<String>,
+}
+
+impl Workspace {
+    const fn new(id: u64) -> Self {
+        Self {
+            id,
+            name: String::new(),
+            coordinates: vec![],
+            state: WorkspaceState::empty(),
+            group: None,
+            outputs: vec![],
+        }
+    }
+
+    /// Identifies the workspace until it is removed, never reused.
+    #[must_use]
+    pub const fn id(&self) -> u64 {
+        self.id
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Position in a grid of workspaces, empty when the compositor doesn't lay them out.
+    #[must_use]
+    pub fn coordinates(&self) -> &[u32] {
+        &self.coordinates
+    }
+
+    #[must_use]
+    pub const fn state(&self) -> WorkspaceState {
+        self.state
+    }
+
+    /// [Id](WorkspaceGroup::id) of the group the workspace belongs to.
+    #[must_use]
+    pub const fn group(&self) -> Option<u64> {
+        self.group
+    }
+
+    /// Names of the outputs of its group, see [`Output::name`](crate::output::Output::name).
+    #[must_use]
+    pub fn outputs(&self) -> &[String] {
+        &self.outputs
+    }
+}
+
+/// Workspaces sharing the same outputs, usually one group per output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceGroup {
+    id: u64,
+    outputs: Vec<String>,
+}
+
+impl WorkspaceGroup {
+    #[must_use]
+    pub const fn id(&self) -> u64 {
+        self.id
+    }
+
+    #[must_use]
+    pub fn outputs(&self) -> &[String] {
+        &self.outputs
+    }
+}
+
+#[derive(Debug)]
+struct GroupEntry {
+    handle: ExtWorkspaceGroupHandleV1,
+    /// State being received until the next `done` of the manager.
+    pending: WorkspaceGroup,
+    workspaces: Vec<ExtWorkspaceHandleV1>,
+}
+
+#[derive(Debug)]
+struct WorkspaceEntry {
+    handle: ExtWorkspaceHandleV1,
+    pending: Workspace,
+}
+
+#[derive(Default, Debug)]
+pub struct Workspaces {
+    group_entries: Vec<GroupEntry>,
+    entries: Vec<WorkspaceEntry>,
+
+    groups: Vec<WorkspaceGroup>,
+    workspaces: Vec<Workspace>,
+    changed: bool,
+    next_id: u64,
+}
+
+impl Workspaces {
+    /// Workspaces in the order the compositor listed them.
+    pub fn iter(&self) -> impl Iterator<Item = &Workspace> {
+        self.workspaces.iter()
+    }
+
+    #[must_use]
+    pub fn get(&self, id: u64) -> Option<&Workspace> {
+        self.workspaces.iter().find(|w| w.id == id)
+    }
+
+    pub fn groups(&self) -> impl Iterator<Item = &WorkspaceGroup> {
+        self.groups.iter()
+    }
+
+    /// Returns whether the workspaces changed since the previous call.
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    const fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn pending_mut(&mut self, handle: &ExtWorkspaceHandleV1) -> Option<&mut Workspace> {
+        self.entries
+            .iter_mut()
+            .find(|e| e.handle == *handle)
+            .map(|e| &mut e.pending)
+    }
+
+    fn group_mut(&mut self, handle: &ExtWorkspaceGroupHandleV1) -> Option<&mut GroupEntry> {
+        self.group_entries.iter_mut().find(|e| e.handle == *handle)
+    }
+
+    /// Applies everything received since the previous `done` at once.
+    fn commit(&mut self) {
+        let groups: Vec<_> = self
+            .group_entries
+            .iter()
+            .map(|e| e.pending.clone())
+            .collect();
+
+        let workspaces: Vec<_> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut workspace = entry.pending.clone();
+                if let Some(group) = self
+                    .group_entries
+                    .iter()
+                    .find(|g| g.workspaces.contains(&entry.handle))
+                {
+                    workspace.group = Some(group.pending.id);
+                    workspace.outputs.clone_from(&group.pending.outputs);
+                }
+                workspace
+            })
+            .collect();
+
+        if groups != self.groups || workspaces != self.workspaces {
+            self.groups = groups;
+            self.workspaces = workspaces;
+            self.changed = true;
+        }
+    }
+
+    fn remove(&mut self, handle: &ExtWorkspaceHandleV1) {
+        handle.destroy();
+        self.entries.retain(|e| e.handle != *handle);
+        for group in &mut self.group_entries {
+            group.workspaces.retain(|w| w != handle);
+        }
+    }
+
+    fn remove_group(&mut self, handle: &ExtWorkspaceGroupHandleV1) {
+        handle.destroy();
+        self.group_entries.retain(|e| e.handle != *handle);
+    }
+
+    fn handle(&self, id: u64) -> Option<&ExtWorkspaceHandleV1> {
+        self.entries
+            .iter()
+            .find(|e| e.pending.id == id)
+            .map(|e| &e.handle)
+    }
+}
+
+impl WlClient {
+    /// Switches to the workspace, on the outputs of its group.
+    pub fn activate_workspace(&self, id: u64) {
+        if let (Some(handle), Some(manager)) = (self.workspaces.handle(id), &self.workspace_manager)
+        {
+            handle.activate();
+            manager.commit();
+        }
+    }
+
+    #[must_use]
+    pub fn workspaces(&self) -> &Workspaces {
+        &self.workspaces
+    }
+
+    pub fn workspaces_mut(&mut self) -> &mut Workspaces {
+        &mut self.workspaces
+    }
+}
+
+impl Dispatch<ExtWorkspaceManagerV1, WindowId> for WlClient {
+    fn event(
+        client: &mut Self,
+        _: &ExtWorkspaceManagerV1,
+        event: ExtWorkspaceManagerV1Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let workspaces = &mut client.workspaces;
+        match event {
+            ExtWorkspaceManagerV1Event::WorkspaceGroup { workspace_group } => {
+                let id = workspaces.next_id();
+                workspaces.group_entries.push(GroupEntry {
+                    handle: workspace_group,
+                    pending: WorkspaceGroup {
+                        id,
+                        outputs: vec![],
+                    },
+                    workspaces: vec![],
+                });
+            }
+            ExtWorkspaceManagerV1Event::Workspace { workspace } => {
+                let id = workspaces.next_id();
+                workspaces.entries.push(WorkspaceEntry {
+                    handle: workspace,
+                    pending: Workspace::new(id),
+                });
+            }
+            ExtWorkspaceManagerV1Event::Done => workspaces.commit(),
+            ExtWorkspaceManagerV1Event::Finished => {
+                client.workspace_manager = None;
+            }
+            _ => {}
+        }
+    }
+
+    event_created_child!(WlClient, ExtWorkspaceManagerV1, [
+        ext_workspace_manager_v1::EVT_WORKSPACE_GROUP_OPCODE => (ExtWorkspaceGroupHandleV1, WindowId::default()),
+        ext_workspace_manager_v1::EVT_WORKSPACE_OPCODE => (ExtWorkspaceHandleV1, WindowId::default()),
+    ]);
+}
+
+impl Dispatch<ExtWorkspaceGroupHandleV1, WindowId> for WlClient {
+    fn event(
+        client: &mut Self,
+        proxy: &ExtWorkspaceGroupHandleV1,
+        event: ExtWorkspaceGroupHandleV1Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let ExtWorkspaceGroupHandleV1Event::Removed = event {
+            client.workspaces.remove_group(proxy);
+            return;
+        }
+
+        let output_name = match &event {
+            ExtWorkspaceGroupHandleV1Event::OutputEnter { output }
+            | ExtWorkspaceGroupHandleV1Event::OutputLeave { output } => {
+                client.outputs.name_of(output).map(ToString::to_string)
+            }
+            _ => None,
+        };
+
+        let Some(group) = client.workspaces.group_mut(proxy) else {
+            return;
+        };
+
+        match event {
+            ExtWorkspaceGroupHandleV1Event::OutputEnter { .. } => {
+                if let Some(name) = output_name
+                    && !group.pending.outputs.contains(&name)
+                {
+                    group.pending.outputs.push(name);
+                }
+            }
+            ExtWorkspaceGroupHandleV1Event::OutputLeave { .. } => {
+                group
+                    .pending
+                    .outputs
+                    .retain(|o| Some(o) != output_name.as_ref());
+            }
+            ExtWorkspaceGroupHandleV1Event::WorkspaceEnter { workspace }
+                if !group.workspaces.contains(&workspace) =>
+            {
+                group.workspaces.push(workspace);
+            }
+            ExtWorkspaceGroupHandleV1Event::WorkspaceLeave { workspace } => {
+                group.workspaces.retain(|w| *w != workspace);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ExtWorkspaceHandleV1, WindowId> for WlClient {
+    fn event(
+        client: &mut Self,
+        proxy: &ExtWorkspaceHandleV1,
+        event: ExtWorkspaceHandleV1Event,
+        _: &WindowId,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let ExtWorkspaceHandleV1Event::Removed = event {
+            client.workspaces.remove(proxy);
+            return;
+        }
+
+        let Some(pending) = client.workspaces.pending_mut(proxy) else {
+            return;
+        };
+
+        match event {
+            ExtWorkspaceHandleV1Event::Name { name } => pending.name = name,
+            ExtWorkspaceHandleV1Event::Coordinates { coordinates } => {
+                pending.coordinates = coordinates
+                    .chunks_exact(4)
+                    .map(|chunk| u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                    .collect();
+            }
+            ExtWorkspaceHandleV1Event::State {
+                state: WEnum::Value(state),
+            } => pending.state = state.into(),
+            _ => {}
+        }
+    }
+}